@@ -0,0 +1,21 @@
+use crate::model::vec3::Vec3;
+
+use super::Background;
+
+// A background computed by an arbitrary function of the (not necessarily
+// unit length) ray direction.
+pub struct FnBackground<F: Fn(&Vec3) -> Vec3 + Send + Sync> {
+    f: F,
+}
+
+impl<F: Fn(&Vec3) -> Vec3 + Send + Sync> FnBackground<F> {
+    pub fn new(f: F) -> Self {
+        Self { f }
+    }
+}
+
+impl<F: Fn(&Vec3) -> Vec3 + Send + Sync> Background for FnBackground<F> {
+    fn value(&self, dir: &Vec3) -> Vec3 {
+        (self.f)(dir)
+    }
+}
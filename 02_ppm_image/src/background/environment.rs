@@ -0,0 +1,173 @@
+use std::{path::Path, sync::Arc};
+
+use crate::{
+    loader::{
+        image::{load_image, Image},
+        LoadError,
+    },
+    model::{sphere::Sphere, vec3::Vec3},
+    util::{distribution::Distribution2D, rtweekend::PI},
+};
+
+use super::Background;
+
+// An equirectangular (latitude/longitude) image surrounding the scene, laid
+// out like the UVs of a sphere: +y is straight up (top row) and u wraps
+// around the vertical axis. Usually loaded from a Radiance .hdr or PFM file.
+pub struct EnvironmentMap {
+    image: Arc<Image>,
+    rotation: f64,
+    intensity: f64,
+    distribution: Distribution2D,
+}
+
+impl EnvironmentMap {
+    // `rotation` turns the map about the vertical axis, in degrees;
+    // `intensity` scales every texel.
+    pub fn new(image: Arc<Image>, rotation: f64, intensity: f64) -> Self {
+        // Texels near the poles cover less solid angle, so weight each row's
+        // luminance by sin(theta) at its center.
+        let (width, height) = (image.width(), image.height());
+        let mut weights = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let sin_theta = (PI * (y as f64 + 0.5) / height as f64).sin();
+            weights.extend((0..width).map(|x| image.pixel(x, y).luminance().max(0.0) * sin_theta));
+        }
+
+        Self {
+            distribution: Distribution2D::new(&weights, width, height),
+            image,
+            rotation,
+            intensity,
+        }
+    }
+
+    pub fn load(path: &Path, rotation: f64, intensity: f64) -> Result<Self, LoadError> {
+        Ok(EnvironmentMap::new(
+            Arc::new(load_image(path)?),
+            rotation,
+            intensity,
+        ))
+    }
+
+    // Map coordinates in [0,1)² for a direction, after rotation.
+    fn uv(&self, dir: &Vec3) -> (f64, f64) {
+        let (u, v) = Sphere::get_sphere_uv(&dir.unit_vector());
+        ((u - self.rotation / 360.0).rem_euclid(1.0), v)
+    }
+
+    // Inverse of `uv`: the unit direction for map coordinates (u, v).
+    fn direction(&self, u: f64, v: f64) -> Vec3 {
+        let theta = v * PI;
+        let phi = 2.0 * PI * (u + self.rotation / 360.0) - PI;
+        Vec3::new(
+            theta.sin() * phi.cos(),
+            -theta.cos(),
+            -theta.sin() * phi.sin(),
+        )
+    }
+
+    fn texel(&self, u: f64, v: f64) -> Vec3 {
+        let width = self.image.width();
+        let height = self.image.height();
+        let x = ((u * width as f64) as usize).min(width - 1);
+        let y = (((1.0 - v) * height as f64) as usize).min(height - 1);
+        *self.image.pixel(x, y)
+    }
+}
+
+impl Background for EnvironmentMap {
+    fn value(&self, dir: &Vec3) -> Vec3 {
+        let (u, v) = self.uv(dir);
+        self.intensity * self.texel(u, v)
+    }
+
+    fn sample(&self, u0: f64, u1: f64) -> Option<(Vec3, f64)> {
+        // The distribution runs over image coordinates, top row first.
+        let ((x, y), map_pdf) = self.distribution.sample_continuous(u0, u1);
+        let v = 1.0 - y;
+        let sin_theta = (v * PI).sin();
+        if map_pdf == 0.0 || sin_theta == 0.0 {
+            return None;
+        }
+
+        // Change of variables from the unit square to the sphere:
+        // dω = 2π² sin(θ) du dv.
+        Some((self.direction(x, v), map_pdf / (2.0 * PI * PI * sin_theta)))
+    }
+
+    fn pdf(&self, dir: &Vec3) -> f64 {
+        let (u, v) = self.uv(dir);
+        let sin_theta = (v * PI).sin();
+        if sin_theta == 0.0 {
+            return 0.0;
+        }
+        self.distribution.pdf(u, 1.0 - v) / (2.0 * PI * PI * sin_theta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 4x2 map: a bright top row and a dark bottom row, with a different
+    // color in each column of the top row.
+    fn map(rotation: f64) -> EnvironmentMap {
+        let mut pixels: Vec<Vec3> = (0..4).map(|i| Vec3::new(i as f64, 1.0, 1.0)).collect();
+        pixels.extend((0..4).map(|_| Vec3::new(0.0, 0.0, 0.0)));
+        EnvironmentMap::new(Arc::new(Image::new(4, 2, pixels)), rotation, 2.0)
+    }
+
+    #[test]
+    fn test_environment_lookup() {
+        let env = map(0.0);
+        assert_eq!(Vec3::default(), env.value(&Vec3::new(0.0, -1.0, 0.0)));
+
+        // Looking along -x sits at u = 0; +z at u = 0.25.
+        let up_and_out = |x: f64, z: f64| Vec3::new(x, 1.0, z);
+        assert_eq!(Vec3::new(0.0, 2.0, 2.0), env.value(&up_and_out(-1.0, 0.01)));
+        assert_eq!(Vec3::new(2.0, 2.0, 2.0), env.value(&up_and_out(0.01, 1.0)));
+    }
+
+    #[test]
+    fn test_environment_sampling() {
+        let env = map(30.0);
+        // Only the top row has any light, so samples all point upwards and
+        // land on the texel they were drawn from.
+        for (u0, u1) in [(0.1, 0.2), (0.6, 0.9), (0.95, 0.5)] {
+            let (dir, pdf) = env.sample(u0, u1).unwrap();
+            assert!(dir.y() > 0.0);
+            assert!((dir.length() - 1.0).abs() < 1e-12);
+            assert!((pdf - env.pdf(&dir)).abs() < 1e-9);
+            assert!(env.value(&dir).y() > 0.0);
+        }
+        assert_eq!(0.0, env.pdf(&Vec3::new(0.3, -1.0, 0.2)));
+
+        // Integrating the pdf over the sphere gives one.
+        let n = 400;
+        let mut total = 0.0;
+        for i in 0..n {
+            for j in 0..n {
+                let theta = PI * (i as f64 + 0.5) / n as f64;
+                let phi = 2.0 * PI * (j as f64 + 0.5) / n as f64;
+                let dir = Vec3::new(
+                    theta.sin() * phi.cos(),
+                    theta.cos(),
+                    theta.sin() * phi.sin(),
+                );
+                total += env.pdf(&dir) * theta.sin() * (PI / n as f64) * (2.0 * PI / n as f64);
+            }
+        }
+        assert!((total - 1.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_environment_rotation() {
+        // A quarter turn moves each column one step along u.
+        let env = map(90.0);
+        assert_eq!(
+            Vec3::new(0.0, 2.0, 2.0),
+            env.value(&Vec3::new(0.01, 1.0, 1.0))
+        );
+    }
+}
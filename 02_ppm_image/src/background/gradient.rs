@@ -0,0 +1,56 @@
+use crate::model::vec3::Vec3;
+
+use super::Background;
+
+// Blends linearly from `bottom` (looking straight down the up axis) to `top`
+// (looking straight up it).
+pub struct GradientBackground {
+    bottom: Vec3,
+    top: Vec3,
+    up: Vec3,
+}
+
+impl GradientBackground {
+    pub fn new(bottom: &Vec3, top: &Vec3, up: &Vec3) -> Self {
+        Self {
+            bottom: *bottom,
+            top: *top,
+            up: up.unit_vector(),
+        }
+    }
+
+    // White at the horizon fading to light blue overhead.
+    pub fn sky() -> Self {
+        GradientBackground::new(
+            &Vec3::new(1.0, 1.0, 1.0),
+            &Vec3::new(0.5, 0.7, 1.0),
+            &Vec3::new(0.0, 1.0, 0.0),
+        )
+    }
+}
+
+impl Background for GradientBackground {
+    fn value(&self, dir: &Vec3) -> Vec3 {
+        let t = 0.5 * (dir.unit_vector().dot(&self.up) + 1.0);
+        (1.0 - t) * self.bottom + t * self.top
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gradient_follows_up_axis() {
+        let black = Vec3::new(0.0, 0.0, 0.0);
+        let white = Vec3::new(1.0, 1.0, 1.0);
+        let z_up = GradientBackground::new(&black, &white, &Vec3::new(0.0, 0.0, 2.0));
+
+        assert_eq!(white, z_up.value(&Vec3::new(0.0, 0.0, 5.0)));
+        assert_eq!(black, z_up.value(&Vec3::new(0.0, 0.0, -1.0)));
+        assert_eq!(
+            Vec3::new(0.5, 0.5, 0.5),
+            z_up.value(&Vec3::new(0.0, 3.0, 0.0))
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use crate::model::vec3::Vec3;
+
+pub mod closure;
+pub mod environment;
+pub mod gradient;
+pub mod solid;
+
+// The radiance arriving from infinitely far away along a direction, seen by
+// rays that escape the scene without hitting anything.
+pub trait Background: Send + Sync {
+    fn value(&self, dir: &Vec3) -> Vec3;
+
+    // Picks a unit direction towards the background from uniform (u0, u1),
+    // favoring bright regions, and returns it with its solid-angle density.
+    // Backgrounds that are not worth sampling explicitly return None.
+    fn sample(&self, _u0: f64, _u1: f64) -> Option<(Vec3, f64)> {
+        None
+    }
+
+    // The solid-angle density with which `sample` would pick `dir`.
+    fn pdf(&self, _dir: &Vec3) -> f64 {
+        0.0
+    }
+}
@@ -0,0 +1,25 @@
+use crate::model::vec3::Vec3;
+
+use super::Background;
+
+// The same color in every direction; black gives a scene lit only by its
+// emitters.
+pub struct SolidBackground {
+    color: Vec3,
+}
+
+impl SolidBackground {
+    pub fn new(color: &Vec3) -> Self {
+        Self { color: *color }
+    }
+
+    pub fn black() -> Self {
+        SolidBackground::new(&Vec3::new(0.0, 0.0, 0.0))
+    }
+}
+
+impl Background for SolidBackground {
+    fn value(&self, _dir: &Vec3) -> Vec3 {
+        self.color
+    }
+}
@@ -0,0 +1,155 @@
+use crate::{filter::Filter, model::vec3::Vec3};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct FilmPixel {
+    color_sum: Vec3,
+    weight_sum: f64,
+}
+
+impl FilmPixel {
+    fn color(&self) -> Vec3 {
+        if self.weight_sum == 0.0 {
+            return Vec3::default();
+        }
+        self.color_sum / self.weight_sum
+    }
+}
+
+// Accumulates filter-weighted samples, as linear HDR RGB sums plus the sum of
+// their weights, for the pixels [x0, x1) × [y0, y1) of an image, with y = 0
+// the top row. Film positions are continuous: pixel (x, y) covers
+// [x, x+1) × [y, y+1) and its center is at (x+0.5, y+0.5). The writer module
+// saves a film as an image file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Film {
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    pixels: Vec<FilmPixel>,
+}
+
+impl Film {
+    pub fn new(width: usize, height: usize) -> Self {
+        Film::with_bounds(0, 0, width, height)
+    }
+
+    // A film for only part of an image, e.g. the pixels one tile's samples
+    // can reach.
+    pub fn with_bounds(x0: usize, y0: usize, x1: usize, y1: usize) -> Self {
+        Self {
+            x0,
+            y0,
+            x1,
+            y1,
+            pixels: vec![FilmPixel::default(); (x1 - x0) * (y1 - y0)],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.x1 - self.x0
+    }
+
+    pub fn height(&self) -> usize {
+        self.y1 - self.y0
+    }
+
+    // Adds a sample taken at film position (x, y) to every pixel whose center
+    // lies within the filter's radius. A sample exactly on the radius counts
+    // toward the pixels on its right and below.
+    pub fn add_sample(&mut self, x: f64, y: f64, color: &Vec3, filter: &dyn Filter) {
+        let r = filter.radius();
+        let (px0, px1) = self.pixel_range(x, r, self.x0, self.x1);
+        let (py0, py1) = self.pixel_range(y, r, self.y0, self.y1);
+        for py in py0..py1 {
+            for px in px0..px1 {
+                let weight = filter.evaluate(px as f64 + 0.5 - x, py as f64 + 0.5 - y);
+                if weight == 0.0 {
+                    continue;
+                }
+                let pixel = self.pixel_mut(px, py);
+                pixel.color_sum += weight * color;
+                pixel.weight_sum += weight;
+            }
+        }
+    }
+
+    // Pixels in [lo, hi) with centers in (p - r, p + r].
+    fn pixel_range(&self, p: f64, r: f64, lo: usize, hi: usize) -> (usize, usize) {
+        let first = (p - r - 0.5).floor() + 1.0;
+        let last = (p + r - 0.5).floor() + 1.0;
+        (
+            first.clamp(lo as f64, hi as f64) as usize,
+            last.clamp(lo as f64, hi as f64) as usize,
+        )
+    }
+
+    fn pixel_mut(&mut self, x: usize, y: usize) -> &mut FilmPixel {
+        let width = self.width();
+        &mut self.pixels[(y - self.y0) * width + (x - self.x0)]
+    }
+
+    // Adds another film's samples to the pixels the two films share.
+    pub fn merge(&mut self, other: &Film) {
+        for y in self.y0.max(other.y0)..self.y1.min(other.y1) {
+            for x in self.x0.max(other.x0)..self.x1.min(other.x1) {
+                let theirs = other.pixels[(y - other.y0) * other.width() + (x - other.x0)];
+                let pixel = self.pixel_mut(x, y);
+                pixel.color_sum += theirs.color_sum;
+                pixel.weight_sum += theirs.weight_sum;
+            }
+        }
+    }
+
+    // The weighted average of the samples that reached pixel (x, y), or black
+    // if none did.
+    pub fn pixel(&self, x: usize, y: usize) -> Vec3 {
+        self.pixels[(y - self.y0) * self.width() + (x - self.x0)].color()
+    }
+
+    // Every pixel's color, top row first.
+    pub fn colors(&self) -> impl Iterator<Item = Vec3> + '_ {
+        self.pixels.iter().map(FilmPixel::color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::{box_filter::BoxFilter, tent::TentFilter};
+
+    #[test]
+    fn test_box_filter_keeps_samples_in_their_pixel() {
+        let mut film = Film::new(3, 2);
+        let filter = BoxFilter::new(0.5);
+        film.add_sample(1.0, 0.0, &Vec3::new(1.0, 1.0, 1.0), &filter);
+        film.add_sample(1.999, 0.999, &Vec3::new(3.0, 3.0, 3.0), &filter);
+        assert_eq!(Vec3::new(2.0, 2.0, 2.0), film.pixel(1, 0));
+        assert_eq!(Vec3::default(), film.pixel(0, 0));
+        assert_eq!(Vec3::default(), film.pixel(2, 0));
+        assert_eq!(Vec3::default(), film.pixel(1, 1));
+    }
+
+    #[test]
+    fn test_wide_filter_splats_into_neighbours() {
+        let mut film = Film::new(3, 3);
+        let filter = TentFilter::new(1.5);
+        film.add_sample(1.5, 1.5, &Vec3::new(1.0, 0.0, 0.0), &filter);
+        film.add_sample(0.5, 0.5, &Vec3::new(0.0, 1.0, 0.0), &filter);
+        // The center pixel weighs its own sample 2.25 and the corner's 0.25.
+        assert!((film.pixel(1, 1) - Vec3::new(0.9, 0.1, 0.0)).near_zero());
+        assert!((film.pixel(2, 2) - Vec3::new(1.0, 0.0, 0.0)).near_zero());
+    }
+
+    #[test]
+    fn test_merge_adds_overlapping_pixels() {
+        let filter = BoxFilter::new(0.5);
+        let mut film = Film::new(4, 4);
+        let mut part = Film::with_bounds(1, 2, 3, 4);
+        part.add_sample(2.5, 3.5, &Vec3::new(1.0, 1.0, 1.0), &filter);
+        film.add_sample(2.5, 3.5, &Vec3::new(3.0, 3.0, 3.0), &filter);
+        film.merge(&part);
+        assert_eq!(Vec3::new(2.0, 2.0, 2.0), film.pixel(2, 3));
+        assert_eq!(Vec3::default(), film.pixel(1, 2));
+    }
+}
@@ -0,0 +1,45 @@
+use crate::util::rtweekend::PI;
+
+use super::Filter;
+
+// A sinc windowed by a wider sinc (Lanczos-`tau`); the window's first zero is
+// at `tau`, normally the radius. Sharpest of the filters, with the most
+// ringing around high-contrast edges.
+pub struct LanczosFilter {
+    radius: f64,
+    tau: f64,
+}
+
+impl LanczosFilter {
+    pub fn new(radius: f64) -> Self {
+        Self::with_tau(radius, radius)
+    }
+
+    pub fn with_tau(radius: f64, tau: f64) -> Self {
+        Self { radius, tau }
+    }
+
+    fn windowed_sinc(&self, x: f64) -> f64 {
+        if x.abs() > self.radius {
+            return 0.0;
+        }
+        sinc(x) * sinc(x / self.tau)
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-5 {
+        return 1.0;
+    }
+    (PI * x).sin() / (PI * x)
+}
+
+impl Filter for LanczosFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn evaluate(&self, x: f64, y: f64) -> f64 {
+        self.windowed_sinc(x) * self.windowed_sinc(y)
+    }
+}
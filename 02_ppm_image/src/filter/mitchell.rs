@@ -0,0 +1,61 @@
+use super::Filter;
+
+// The Mitchell–Netravali cubic, stretched over the radius. B and C trade
+// blurring (large B) against ringing (large C); the default B = C = 1/3 is the
+// authors' recommended compromise.
+pub struct MitchellFilter {
+    radius: f64,
+    b: f64,
+    c: f64,
+}
+
+impl MitchellFilter {
+    pub fn new(radius: f64) -> Self {
+        Self::with_parameters(radius, 1.0 / 3.0, 1.0 / 3.0)
+    }
+
+    pub fn with_parameters(radius: f64, b: f64, c: f64) -> Self {
+        Self { radius, b, c }
+    }
+
+    // The cubic over its natural support of [-2, 2].
+    fn mitchell(&self, x: f64) -> f64 {
+        let (b, c) = (self.b, self.c);
+        let x = x.abs();
+        let value = if x <= 1.0 {
+            (12.0 - 9.0 * b - 6.0 * c) * x * x * x
+                + (-18.0 + 12.0 * b + 6.0 * c) * x * x
+                + (6.0 - 2.0 * b)
+        } else if x <= 2.0 {
+            (-b - 6.0 * c) * x * x * x
+                + (6.0 * b + 30.0 * c) * x * x
+                + (-12.0 * b - 48.0 * c) * x
+                + (8.0 * b + 24.0 * c)
+        } else {
+            0.0
+        };
+        value / 6.0
+    }
+}
+
+impl Filter for MitchellFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn evaluate(&self, x: f64, y: f64) -> f64 {
+        self.mitchell(2.0 * x / self.radius) * self.mitchell(2.0 * y / self.radius)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mitchell_has_negative_lobes() {
+        let filter = MitchellFilter::new(2.0);
+        assert!((filter.evaluate(0.0, 0.0) - (8.0 / 9.0) * (8.0 / 9.0)).abs() < 1e-12);
+        assert!(filter.evaluate(1.5, 0.0) < 0.0);
+    }
+}
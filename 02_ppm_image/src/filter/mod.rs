@@ -0,0 +1,48 @@
+pub mod box_filter;
+pub mod gaussian;
+pub mod lanczos;
+pub mod mitchell;
+pub mod tent;
+
+// A pixel reconstruction filter: how much a sample counts towards a pixel,
+// by its offset from the pixel's center. Each pixel is the filter-weighted
+// average of the samples within `radius` of its center on both axes, so
+// wider filters blur more and filters with negative lobes sharpen, at the
+// risk of ringing.
+pub trait Filter: Send + Sync {
+    // Half-width, in pixels, of the square the filter is non-zero over.
+    fn radius(&self) -> f64;
+
+    fn evaluate(&self, x: f64, y: f64) -> f64;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        box_filter::BoxFilter, gaussian::GaussianFilter, lanczos::LanczosFilter,
+        mitchell::MitchellFilter, tent::TentFilter, Filter,
+    };
+
+    #[test]
+    fn test_filters_peak_at_center_and_vanish_at_radius() {
+        let filters: [Box<dyn Filter>; 5] = [
+            Box::new(BoxFilter::new(0.5)),
+            Box::new(TentFilter::new(1.5)),
+            Box::new(GaussianFilter::new(1.5)),
+            Box::new(MitchellFilter::new(2.0)),
+            Box::new(LanczosFilter::new(3.0)),
+        ];
+        for filter in filters.iter() {
+            let r = filter.radius();
+            let center = filter.evaluate(0.0, 0.0);
+            assert!(center > 0.0);
+            assert!(filter.evaluate(0.3 * r, 0.2 * r) <= center);
+            assert_eq!(
+                filter.evaluate(0.4 * r, 0.4 * r),
+                filter.evaluate(-0.4 * r, 0.4 * r)
+            );
+            assert!(filter.evaluate(r + 0.01, 0.0).abs() < 1e-12);
+            assert!(filter.evaluate(0.0, -r - 0.01).abs() < 1e-12);
+        }
+    }
+}
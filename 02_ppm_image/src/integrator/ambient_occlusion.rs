@@ -0,0 +1,110 @@
+use crate::{
+    model::{hit::HitRecord, onb::Onb, ray::Ray, vec3::Vec3},
+    sampler::Sampler,
+    scene::Scene,
+    util::rtweekend::INFINITY,
+};
+
+use super::Integrator;
+
+// Shades the first hit by how much of its hemisphere is open: the fraction of
+// cosine-weighted rays that travel `max_distance` without hitting anything.
+// Materials and lights are ignored; rays that miss the scene are white.
+pub struct AmbientOcclusion {
+    pub samples: usize,
+    pub max_distance: f64,
+}
+
+impl AmbientOcclusion {
+    pub fn new(samples: usize, max_distance: f64) -> Self {
+        Self {
+            samples,
+            max_distance,
+        }
+    }
+}
+
+impl Integrator for AmbientOcclusion {
+    fn li(&self, r: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Vec3 {
+        let mut rec = HitRecord::default();
+        if !scene.world.hit(r, 0.001, INFINITY, &mut rec, sampler) {
+            return Vec3::new(1.0, 1.0, 1.0);
+        }
+
+        let uvw = Onb::build_from_w(&rec.normal);
+        let mut open = 0;
+        for _ in 0..self.samples {
+            let dir = uvw.local(&Vec3::random_cosine_direction(sampler));
+            let probe = Ray::new(&rec.p, &dir, r.time());
+            let mut blocker = HitRecord::default();
+            if !scene
+                .world
+                .hit(&probe, 0.001, self.max_distance, &mut blocker, sampler)
+            {
+                open += 1;
+            }
+        }
+
+        let visibility = open as f64 / self.samples.max(1) as f64;
+        Vec3::new(visibility, visibility, visibility)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        background::solid::SolidBackground,
+        material::lambertian::Lambertian,
+        model::{hit::HittableList, quad::Quad},
+        sampler::independent::IndependentSampler,
+    };
+
+    use Vec3 as Point3;
+
+    #[test]
+    fn test_ambient_occlusion() {
+        let gray = Arc::new(Lambertian::new(&Vec3::new(0.5, 0.5, 0.5)));
+        let floor = Quad::new(
+            Point3::new(-1.0, 0.0, -1.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 2.0),
+            gray.clone(),
+        );
+        let mut world = HittableList::new();
+        world.add(Arc::new(floor));
+        let open = Scene::new(Arc::new(world), Arc::new(SolidBackground::black()));
+
+        let ao = AmbientOcclusion::new(16, 10.0);
+        let mut sampler = IndependentSampler::new(0);
+        let down = Ray::new(&Point3::new(0.0, 1.0, 0.0), &Vec3::new(0.0, -1.0, 0.0), 0.0);
+        assert_eq!(Vec3::new(1.0, 1.0, 1.0), ao.li(&down, &open, &mut sampler));
+
+        // A ceiling just above the floor blocks every probe.
+        let mut world = HittableList::new();
+        world.add(Arc::new(Quad::new(
+            Point3::new(-1.0, 0.0, -1.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 2.0),
+            gray.clone(),
+        )));
+        world.add(Arc::new(Quad::new(
+            Point3::new(-50.0, 0.01, -50.0),
+            Vec3::new(100.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 100.0),
+            gray,
+        )));
+        let closed = Scene::new(Arc::new(world), Arc::new(SolidBackground::black()));
+        let below = Ray::new(
+            &Point3::new(0.0, 0.005, 0.0),
+            &Vec3::new(0.0, -1.0, 0.0),
+            0.0,
+        );
+        assert_eq!(
+            Vec3::new(0.0, 0.0, 0.0),
+            ao.li(&below, &closed, &mut sampler)
+        );
+    }
+}
@@ -0,0 +1,15 @@
+use crate::{
+    model::{ray::Ray, vec3::Vec3},
+    sampler::Sampler,
+    scene::Scene,
+};
+
+pub mod ambient_occlusion;
+pub mod path;
+pub mod whitted;
+
+// A rendering algorithm: estimates the light arriving back along a camera
+// ray. Averaging many estimates per pixel gives the pixel's color.
+pub trait Integrator: Send + Sync {
+    fn li(&self, r: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Vec3;
+}
@@ -0,0 +1,164 @@
+use crate::{
+    material::material::ScatterRecord,
+    model::{
+        hit::{HitRecord, Hittable},
+        ray::Ray,
+        vec3::Vec3,
+    },
+    sampler::Sampler,
+    scene::Scene,
+    util::rtweekend::INFINITY,
+};
+
+use super::Integrator;
+
+// Unidirectional path tracing. At every non-specular hit, lights and the
+// background are sampled directly and combined with the BSDF-sampled bounce
+// by multiple importance sampling. After `min_bounces`, paths are ended at
+// random with a probability that grows as their throughput falls (Russian
+// roulette); survivors are boosted to compensate, so the estimate stays
+// unbiased.
+pub struct PathTracer {
+    pub min_bounces: usize,
+}
+
+impl PathTracer {
+    pub fn new(min_bounces: usize) -> Self {
+        Self { min_bounces }
+    }
+}
+
+impl Integrator for PathTracer {
+    fn li(&self, r: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Vec3 {
+        let mut radiance = Vec3::new(0.0, 0.0, 0.0);
+        // The product of the attenuations so far, scaling each bounce's
+        // contribution.
+        let mut throughput = Vec3::new(1.0, 1.0, 1.0);
+        let mut ray = Ray::new(r.origin(), r.dir(), r.time());
+        // The density the previous bounce sampled `ray` with, or None when it
+        // came from the camera or a specular lobe. Emission found by `ray`
+        // competes with the light sampling done at that bounce, so it is
+        // weighted against the density light sampling would have had.
+        let mut bsdf_pdf: Option<f64> = None;
+        let mut bounces = 0;
+
+        loop {
+            let mut rec = HitRecord::default();
+            if !scene.world.hit(&ray, 0.001, INFINITY, &mut rec, sampler) {
+                let weight = bsdf_pdf.map_or(1.0, |pdf| {
+                    power_heuristic(pdf, scene.background.pdf(ray.dir()))
+                });
+                radiance += throughput * weight * scene.background.value(ray.dir());
+                break;
+            }
+
+            let mut emitted = rec.material.emitted(rec.u, rec.v, &rec.p);
+            if let Some(pdf) = bsdf_pdf {
+                emitted *= power_heuristic(pdf, scene.lights.pdf_value(ray.origin(), ray.dir()));
+            }
+            radiance += throughput * emitted;
+
+            let mut srec = ScatterRecord::default();
+            if !rec.material.sample(&ray, &rec, &mut srec, sampler) {
+                break;
+            }
+
+            // Delta lobes can't be reached by a shadow ray; follow them as they are.
+            if srec.is_specular {
+                bsdf_pdf = None;
+            } else {
+                radiance += throughput * sample_lights(&ray, &rec, scene, sampler);
+                bsdf_pdf = Some(srec.pdf);
+            }
+
+            throughput *= srec.attenuation;
+            if throughput.near_zero() {
+                break;
+            }
+
+            bounces += 1;
+            if bounces >= self.min_bounces {
+                // Capped below one so paths between perfect mirrors still end.
+                let survival = throughput
+                    .x()
+                    .max(throughput.y())
+                    .max(throughput.z())
+                    .min(0.95);
+                if sampler.get_1d() >= survival {
+                    break;
+                }
+                throughput /= survival;
+            }
+
+            ray = srec.scattered;
+        }
+
+        radiance
+    }
+}
+
+// Next-event estimation: one shadow ray towards a point on a random light and
+// one towards the background. Each is weighted against the chance that BSDF
+// sampling finds the same light.
+fn sample_lights(r: &Ray, rec: &HitRecord, scene: &Scene, sampler: &mut dyn Sampler) -> Vec3 {
+    let mut direct = Vec3::new(0.0, 0.0, 0.0);
+
+    if !scene.lights.objects.is_empty() {
+        let dir = scene.lights.random(&rec.p, sampler);
+        let light_pdf = scene.lights.pdf_value(&rec.p, &dir);
+        let shadow = Ray::new(&rec.p, &dir, r.time());
+        let mut light_rec = HitRecord::default();
+        if light_pdf > 0.0
+            && scene
+                .world
+                .hit(&shadow, 0.001, INFINITY, &mut light_rec, sampler)
+        {
+            let emitted = light_rec
+                .material
+                .emitted(light_rec.u, light_rec.v, &light_rec.p);
+            let weight = power_heuristic(light_pdf, rec.material.pdf(r, rec, &shadow));
+            direct += weight * rec.material.eval(r, rec, &shadow) * emitted / light_pdf;
+        }
+    }
+
+    let (u0, u1) = sampler.get_2d();
+    if let Some((dir, env_pdf)) = scene.background.sample(u0, u1) {
+        let shadow = Ray::new(&rec.p, &dir, r.time());
+        let mut blocker = HitRecord::default();
+        if !scene
+            .world
+            .hit(&shadow, 0.001, INFINITY, &mut blocker, sampler)
+        {
+            let weight = power_heuristic(env_pdf, rec.material.pdf(r, rec, &shadow));
+            direct += weight * rec.material.eval(r, rec, &shadow) * scene.background.value(&dir)
+                / env_pdf;
+        }
+    }
+
+    direct
+}
+
+// Veach's power heuristic (β = 2) for the sampling strategy with density
+// `f_pdf`, against one competing strategy with density `g_pdf`.
+fn power_heuristic(f_pdf: f64, g_pdf: f64) -> f64 {
+    let f = f_pdf * f_pdf;
+    let g = g_pdf * g_pdf;
+    if f + g == 0.0 {
+        0.0
+    } else {
+        f / (f + g)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_power_heuristic() {
+        assert_eq!(0.5, power_heuristic(2.0, 2.0));
+        assert_eq!(0.8, power_heuristic(2.0, 1.0));
+        assert_eq!(1.0, power_heuristic(3.0, 0.0));
+        assert_eq!(0.0, power_heuristic(0.0, 0.0));
+    }
+}
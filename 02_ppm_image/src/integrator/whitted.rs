@@ -0,0 +1,169 @@
+use crate::{
+    material::material::ScatterRecord,
+    model::{hit::HitRecord, ray::Ray, vec3::Vec3},
+    sampler::Sampler,
+    scene::Scene,
+    util::rtweekend::INFINITY,
+};
+
+use super::Integrator;
+
+// Classic recursive ray tracing: mirrors and glass are followed up to
+// `max_depth`, every other surface is shaded only by the light reaching it
+// directly (one shadow ray per light, plus one towards the background).
+// There is no indirect diffuse light, so it is fast but not physically
+// complete.
+pub struct WhittedTracer {
+    pub max_depth: usize,
+}
+
+impl WhittedTracer {
+    pub fn new(max_depth: usize) -> Self {
+        Self { max_depth }
+    }
+
+    fn trace(&self, r: &Ray, scene: &Scene, sampler: &mut dyn Sampler, depth: usize) -> Vec3 {
+        let mut rec = HitRecord::default();
+        if !scene.world.hit(r, 0.001, INFINITY, &mut rec, sampler) {
+            return scene.background.value(r.dir());
+        }
+
+        let emitted = rec.material.emitted(rec.u, rec.v, &rec.p);
+
+        let mut srec = ScatterRecord::default();
+        if !rec.material.sample(r, &rec, &mut srec, sampler) {
+            return emitted;
+        }
+
+        if srec.is_specular {
+            if depth >= self.max_depth {
+                return emitted;
+            }
+            return emitted
+                + srec.attenuation * self.trace(&srec.scattered, scene, sampler, depth + 1);
+        }
+
+        emitted + direct_light(r, &rec, scene, sampler)
+    }
+}
+
+impl Integrator for WhittedTracer {
+    fn li(&self, r: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Vec3 {
+        self.trace(r, scene, sampler, 0)
+    }
+}
+
+fn direct_light(r: &Ray, rec: &HitRecord, scene: &Scene, sampler: &mut dyn Sampler) -> Vec3 {
+    let mut direct = Vec3::new(0.0, 0.0, 0.0);
+
+    for light in scene.lights.objects.iter() {
+        let dir = light.random(&rec.p, sampler);
+        let pdf = light.pdf_value(&rec.p, &dir);
+        let shadow = Ray::new(&rec.p, &dir, r.time());
+        let mut light_rec = HitRecord::default();
+        if pdf > 0.0
+            && scene
+                .world
+                .hit(&shadow, 0.001, INFINITY, &mut light_rec, sampler)
+        {
+            let emitted = light_rec
+                .material
+                .emitted(light_rec.u, light_rec.v, &light_rec.p);
+            direct += rec.material.eval(r, rec, &shadow) * emitted / pdf;
+        }
+    }
+
+    let (u0, u1) = sampler.get_2d();
+    if let Some((dir, pdf)) = scene.background.sample(u0, u1) {
+        let shadow = Ray::new(&rec.p, &dir, r.time());
+        let mut blocker = HitRecord::default();
+        if !scene
+            .world
+            .hit(&shadow, 0.001, INFINITY, &mut blocker, sampler)
+        {
+            direct += rec.material.eval(r, rec, &shadow) * scene.background.value(&dir) / pdf;
+        }
+    }
+
+    direct
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        background::solid::SolidBackground,
+        material::{
+            diffuse_light::DiffuseLight, lambertian::Lambertian, material::Material, metal::Metal,
+        },
+        model::{
+            hit::{Hittable, HittableList},
+            quad::Quad,
+        },
+        sampler::independent::IndependentSampler,
+    };
+
+    use Vec3 as Point3;
+
+    // A 2x2 square centered on the y axis at height y.
+    fn square(y: f64, material: Arc<dyn Material>) -> Arc<Quad> {
+        Arc::new(Quad::new(
+            Point3::new(-1.0, y, -1.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 2.0),
+            material,
+        ))
+    }
+
+    fn scene(objects: Vec<Arc<dyn Hittable>>, light: Arc<Quad>) -> Scene {
+        let mut world = HittableList::new();
+        for object in objects {
+            world.add(object);
+        }
+        world.add(light.clone());
+        let mut lights = HittableList::new();
+        lights.add(light);
+        Scene::new(Arc::new(world), Arc::new(SolidBackground::black())).with_lights(lights)
+    }
+
+    #[test]
+    fn test_whitted_mirror_reflects_emitter() {
+        let light = square(2.0, Arc::new(DiffuseLight::new(&Vec3::new(4.0, 4.0, 4.0))));
+        let mirror = square(0.0, Arc::new(Metal::new(&Vec3::new(0.9, 0.9, 0.9), 0.0)));
+        let scene = scene(vec![mirror], light);
+
+        let down = Ray::new(&Point3::new(0.0, 1.0, 0.0), &Vec3::new(0.0, -1.0, 0.0), 0.0);
+        let li = WhittedTracer::new(5).li(&down, &scene, &mut IndependentSampler::new(0));
+        assert!((li - Vec3::new(3.6, 3.6, 3.6)).near_zero());
+
+        // Past the depth limit the reflection is cut off.
+        let li = WhittedTracer::new(0).li(&down, &scene, &mut IndependentSampler::new(0));
+        assert_eq!(Vec3::new(0.0, 0.0, 0.0), li);
+    }
+
+    #[test]
+    fn test_whitted_shadowed_diffuse_point() {
+        let gray: Arc<dyn Material> = Arc::new(Lambertian::new(&Vec3::new(0.5, 0.5, 0.5)));
+        let light = || square(2.0, Arc::new(DiffuseLight::new(&Vec3::new(4.0, 4.0, 4.0))));
+        let down = Ray::new(&Point3::new(0.0, 0.5, 0.0), &Vec3::new(0.0, -1.0, 0.0), 0.0);
+        let whitted = WhittedTracer::new(5);
+        let mut sampler = IndependentSampler::new(0);
+
+        let lit = scene(vec![square(0.0, gray.clone())], light());
+        assert!(whitted.li(&down, &lit, &mut sampler).x() > 0.0);
+
+        // A blocker between the floor and the light leaves the point dark.
+        let shadowed = scene(
+            vec![square(0.0, gray.clone()), square(1.0, gray.clone())],
+            light(),
+        );
+        for _ in 0..16 {
+            assert_eq!(
+                Vec3::new(0.0, 0.0, 0.0),
+                whitted.li(&down, &shadowed, &mut sampler)
+            );
+        }
+    }
+}
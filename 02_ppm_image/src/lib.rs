@@ -0,0 +1,13 @@
+pub mod background;
+pub mod film;
+pub mod filter;
+pub mod integrator;
+pub mod loader;
+pub mod material;
+pub mod model;
+pub mod renderer;
+pub mod sampler;
+pub mod scene;
+pub mod texture;
+pub mod util;
+pub mod writer;
@@ -0,0 +1,323 @@
+use std::path::Path;
+
+use crate::model::vec3::Vec3;
+
+use super::{
+    image::{next_token, Image},
+    LoadError,
+};
+
+// Decodes a Radiance RGBE (.hdr) image. Only the usual "-Y height +X width"
+// orientation is accepted; scanlines may be flat or new-style run-length
+// encoded.
+pub(super) fn decode_rgbe(bytes: &[u8], path: &Path) -> Result<Image, LoadError> {
+    let mut pos = 0;
+    let next_line = |pos: &mut usize| -> Result<String, LoadError> {
+        let end = bytes[*pos..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .ok_or_else(|| LoadError::format(path, "unexpected end of HDR header"))?;
+        let line = String::from_utf8_lossy(&bytes[*pos..*pos + end]).into_owned();
+        *pos += end + 1;
+        Ok(line)
+    };
+
+    // Header lines run up to the first blank line.
+    loop {
+        let line = next_line(&mut pos)?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some(format) = line.strip_prefix("FORMAT=") {
+            if format != "32-bit_rle_rgbe" {
+                return Err(LoadError::format(
+                    path,
+                    format!("unsupported HDR format '{}'", format),
+                ));
+            }
+        }
+    }
+
+    let resolution = next_line(&mut pos)?;
+    let (height, width) = match resolution.split_whitespace().collect::<Vec<_>>()[..] {
+        ["-Y", h, "+X", w] => (h.parse::<usize>().ok(), w.parse::<usize>().ok()),
+        _ => (None, None),
+    };
+    let (Some(height), Some(width)) = (height, width) else {
+        return Err(LoadError::format(
+            path,
+            format!("unsupported HDR resolution line '{}'", resolution),
+        ));
+    };
+    if width == 0 || height == 0 {
+        return Err(LoadError::format(path, "HDR image has no pixels"));
+    }
+
+    let truncated = || LoadError::format(path, "HDR raster truncated");
+    let count = width.checked_mul(height).ok_or_else(|| {
+        LoadError::format(path, format!("HDR size {}x{} too large", width, height))
+    })?;
+    // The fewest bytes a scanline can take: flat pixels, or a header and runs
+    // of at most 127 per channel. Checking the total against what is left
+    // keeps a lying header from making us allocate a huge image.
+    let rle_width = (8..0x8000).contains(&width);
+    let min_scanline = if rle_width {
+        4 + 4 * 2 * width.div_ceil(127)
+    } else {
+        4 * width
+    };
+    let fits = height
+        .checked_mul(min_scanline)
+        .is_some_and(|len| len <= bytes.len() - pos);
+    if !fits {
+        return Err(truncated());
+    }
+    let mut pixels = Vec::with_capacity(count);
+    let mut scanline = vec![[0u8; 4]; width];
+
+    for _ in 0..height {
+        let header = bytes.get(pos..pos + 4).ok_or_else(truncated)?;
+        // A flat pixel can start with 2, 2 too, but its blue byte then has
+        // the high bit set, which no RLE width below 0x8000 has.
+        let rle = rle_width && header[0] == 2 && header[1] == 2 && header[2] & 0x80 == 0;
+
+        if rle {
+            if ((header[2] as usize) << 8 | header[3] as usize) != width {
+                return Err(LoadError::format(path, "HDR scanline width mismatch"));
+            }
+            pos += 4;
+            // Each of the four channels is run-length encoded separately.
+            for channel in 0..4 {
+                let mut x = 0;
+                while x < width {
+                    let count = *bytes.get(pos).ok_or_else(truncated)? as usize;
+                    pos += 1;
+                    if count > 128 {
+                        let run = count - 128;
+                        let value = *bytes.get(pos).ok_or_else(truncated)?;
+                        pos += 1;
+                        if x + run > width {
+                            return Err(LoadError::format(path, "HDR run overflows scanline"));
+                        }
+                        for texel in &mut scanline[x..x + run] {
+                            texel[channel] = value;
+                        }
+                        x += run;
+                    } else {
+                        if count == 0 || x + count > width {
+                            return Err(LoadError::format(path, "bad HDR literal run"));
+                        }
+                        let values = bytes.get(pos..pos + count).ok_or_else(truncated)?;
+                        pos += count;
+                        for (texel, &value) in scanline[x..x + count].iter_mut().zip(values) {
+                            texel[channel] = value;
+                        }
+                        x += count;
+                    }
+                }
+            }
+        } else {
+            let raw = bytes.get(pos..pos + width * 4).ok_or_else(truncated)?;
+            pos += width * 4;
+            for (texel, c) in scanline.iter_mut().zip(raw.chunks_exact(4)) {
+                texel.copy_from_slice(c);
+            }
+        }
+
+        pixels.extend(scanline.iter().map(rgbe_to_vec3));
+    }
+
+    Ok(Image::new(width, height, pixels))
+}
+
+// A shared exponent byte scales all three 8-bit mantissas.
+fn rgbe_to_vec3(rgbe: &[u8; 4]) -> Vec3 {
+    if rgbe[3] == 0 {
+        return Vec3::new(0.0, 0.0, 0.0);
+    }
+    let f = 2f64.powi(rgbe[3] as i32 - (128 + 8));
+    Vec3::new(rgbe[0] as f64 * f, rgbe[1] as f64 * f, rgbe[2] as f64 * f)
+}
+
+// Decodes a Portable Float Map: "PF" (RGB) or "Pf" (grayscale), with a
+// negative scale marking little-endian samples. Rows are stored bottom-up.
+pub(super) fn decode_pfm(bytes: &[u8], path: &Path) -> Result<Image, LoadError> {
+    let invalid = |what: &str, token: &[u8]| {
+        LoadError::format(
+            path,
+            format!("invalid PFM {} '{}'", what, String::from_utf8_lossy(token)),
+        )
+    };
+
+    let channels = if bytes.starts_with(b"PF") { 3 } else { 1 };
+    let (_, pos) = next_token(bytes, 0, path)?;
+    let (token, pos) = next_token(bytes, pos, path)?;
+    let width = std::str::from_utf8(token)
+        .ok()
+        .and_then(|t| t.parse::<usize>().ok())
+        .ok_or_else(|| invalid("width", token))?;
+    let (token, pos) = next_token(bytes, pos, path)?;
+    let height = std::str::from_utf8(token)
+        .ok()
+        .and_then(|t| t.parse::<usize>().ok())
+        .ok_or_else(|| invalid("height", token))?;
+    let (token, pos) = next_token(bytes, pos, path)?;
+    let scale = std::str::from_utf8(token)
+        .ok()
+        .and_then(|t| t.parse::<f64>().ok())
+        .filter(|s| *s != 0.0)
+        .ok_or_else(|| invalid("scale", token))?;
+
+    if width == 0 || height == 0 {
+        return Err(LoadError::format(path, "PFM image has no pixels"));
+    }
+
+    // A single whitespace byte separates the header from the raster.
+    let pos = pos + 1;
+    let count = width
+        .checked_mul(height)
+        .and_then(|n| n.checked_mul(channels))
+        .ok_or_else(|| {
+            LoadError::format(path, format!("PFM size {}x{} too large", width, height))
+        })?;
+    let raster = count
+        .checked_mul(4)
+        .and_then(|len| bytes.get(pos..pos.checked_add(len)?))
+        .ok_or_else(|| {
+            LoadError::format(
+                path,
+                format!(
+                    "PFM raster truncated: expected {} bytes, found {}",
+                    count.saturating_mul(4),
+                    bytes.len().saturating_sub(pos)
+                ),
+            )
+        })?;
+
+    let samples: Vec<f64> = raster
+        .chunks_exact(4)
+        .map(|c| {
+            let b = [c[0], c[1], c[2], c[3]];
+            let value = if scale < 0.0 {
+                f32::from_le_bytes(b)
+            } else {
+                f32::from_be_bytes(b)
+            };
+            value as f64
+        })
+        .collect();
+
+    let row_len = width * channels;
+    let pixels = samples
+        .chunks_exact(row_len)
+        .rev()
+        .flat_map(|row| row.chunks_exact(channels))
+        .map(|c| {
+            if channels == 3 {
+                Vec3::new(c[0], c[1], c[2])
+            } else {
+                Vec3::new(c[0], c[0], c[0])
+            }
+        })
+        .collect();
+
+    Ok(Image::new(width, height, pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::image::parse_image;
+
+    #[test]
+    fn test_parse_flat_hdr() {
+        let mut src = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X 2\n".to_vec();
+        // 128 * 2^(129 - 136) = 1.0; 128 * 2^(131 - 136) = 4.0
+        src.extend_from_slice(&[128, 64, 0, 129, 128, 128, 128, 131]);
+        let image = parse_image(&src, Path::new("a.hdr")).unwrap();
+        assert_eq!(2, image.width());
+        assert_eq!(Vec3::new(1.0, 0.5, 0.0), *image.pixel(0, 0));
+        assert_eq!(Vec3::new(4.0, 4.0, 4.0), *image.pixel(1, 0));
+
+        // Flat pixels that happen to start 2, 2 are not an RLE header.
+        let mut src = b"#?RADIANCE\n\n-Y 1 +X 8\n".to_vec();
+        for _ in 0..8 {
+            src.extend_from_slice(&[2, 2, 128, 136]);
+        }
+        let image = parse_image(&src, Path::new("a.hdr")).unwrap();
+        assert_eq!(Vec3::new(2.0, 2.0, 128.0), *image.pixel(7, 0));
+    }
+
+    #[test]
+    fn test_parse_hdr_huge_header() {
+        let err = parse_image(
+            b"#?RADIANCE\n\n-Y 4294967296 +X 4294967296\n",
+            Path::new("a.hdr"),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            "a.hdr: HDR size 4294967296x4294967296 too large",
+            err.to_string()
+        );
+
+        let err = parse_image(b"#?RADIANCE\n\n-Y 100000 +X 100000\n", Path::new("a.hdr"))
+            .err()
+            .unwrap();
+        assert_eq!("a.hdr: HDR raster truncated", err.to_string());
+
+        let err = parse_image(b"PF 4294967296 4294967296 -1\n", Path::new("a.pfm"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            "a.pfm: PFM size 4294967296x4294967296 too large",
+            err.to_string()
+        );
+
+        let err = parse_image(b"PF 100000 100000 -1\n", Path::new("a.pfm"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            "a.pfm: PFM raster truncated: expected 120000000000 bytes, found 0",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_rle_hdr() {
+        let mut src = b"#?RGBE\n\n-Y 1 +X 8\n".to_vec();
+        src.extend_from_slice(&[2, 2, 0, 8]);
+        // Red: a run of eight 128s. Green: eight literals. Blue: zeros.
+        // Exponent: a run of eight 129s.
+        src.extend_from_slice(&[136, 128]);
+        src.extend_from_slice(&[8, 0, 32, 64, 96, 128, 160, 192, 224]);
+        src.extend_from_slice(&[136, 0]);
+        src.extend_from_slice(&[136, 129]);
+        let image = parse_image(&src, Path::new("a.hdr")).unwrap();
+        assert_eq!(8, image.width());
+        assert_eq!(Vec3::new(1.0, 0.0, 0.0), *image.pixel(0, 0));
+        assert_eq!(Vec3::new(1.0, 0.5, 0.0), *image.pixel(2, 0));
+
+        let err = parse_image(&src[..src.len() - 1], Path::new("a.hdr"))
+            .err()
+            .unwrap();
+        assert_eq!("a.hdr: HDR raster truncated", err.to_string());
+    }
+
+    #[test]
+    fn test_parse_pfm() {
+        let mut src = b"PF\n1 2\n-1.0\n".to_vec();
+        // Bottom row first.
+        for v in [0.25f32, 0.5, 0.75, 2.0, 3.0, 4.0] {
+            src.extend_from_slice(&v.to_le_bytes());
+        }
+        let image = parse_image(&src, Path::new("a.pfm")).unwrap();
+        assert_eq!(Vec3::new(2.0, 3.0, 4.0), *image.pixel(0, 0));
+        assert_eq!(Vec3::new(0.25, 0.5, 0.75), *image.pixel(0, 1));
+
+        let mut gray = b"Pf 1 1 1.0\n".to_vec();
+        gray.extend_from_slice(&1.5f32.to_be_bytes());
+        let image = parse_image(&gray, Path::new("a.pfm")).unwrap();
+        assert_eq!(Vec3::new(1.5, 1.5, 1.5), *image.pixel(0, 0));
+    }
+}
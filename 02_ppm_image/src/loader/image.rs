@@ -0,0 +1,335 @@
+use std::{fs, path::Path};
+
+use crate::model::vec3::Vec3;
+
+use super::{
+    hdr::{decode_pfm, decode_rgbe},
+    LoadError,
+};
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+// A decoded RGB raster, stored top row first. Values are linear: 8/16-bit
+// files are taken as gamma 2.0 encoded, matching what Color::as_color_repr
+// writes, so a rendered image reads back unchanged. Floating-point formats
+// are already linear and may exceed 1.
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Vec3>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, pixels: Vec<Vec3>) -> Self {
+        assert_eq!(width * height, pixels.len());
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // Row 0 is the top of the image.
+    pub fn pixel(&self, x: usize, y: usize) -> &Vec3 {
+        &self.pixels[y * self.width + x]
+    }
+}
+
+// Loads a P3/P6 PPM, PNG, Radiance HDR or PFM file, picking the decoder from
+// the file's magic bytes rather than its extension.
+pub fn load_image(path: &Path) -> Result<Image, LoadError> {
+    let bytes = fs::read(path).map_err(|e| LoadError::io(path, e))?;
+    parse_image(&bytes, path)
+}
+
+pub fn parse_image(bytes: &[u8], path: &Path) -> Result<Image, LoadError> {
+    if bytes.starts_with(&PNG_SIGNATURE) {
+        decode_png(bytes, path)
+    } else if bytes.starts_with(b"P3") || bytes.starts_with(b"P6") {
+        decode_ppm(bytes, path)
+    } else if bytes.starts_with(b"#?") {
+        decode_rgbe(bytes, path)
+    } else if bytes.starts_with(b"PF") || bytes.starts_with(b"Pf") {
+        decode_pfm(bytes, path)
+    } else {
+        Err(LoadError::format(
+            path,
+            "unrecognized image format (expected PPM, PNG, Radiance HDR or PFM)",
+        ))
+    }
+}
+
+fn to_linear(value: f64, max: f64) -> f64 {
+    let encoded = value / max;
+    encoded * encoded
+}
+
+// Reads the next whitespace-separated header token, skipping `#` comments.
+// Returns the token and the index just past it.
+pub(super) fn next_token<'a>(
+    bytes: &'a [u8],
+    mut pos: usize,
+    path: &Path,
+) -> Result<(&'a [u8], usize), LoadError> {
+    loop {
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        if pos < bytes.len() && bytes[pos] == b'#' {
+            while pos < bytes.len() && bytes[pos] != b'\n' {
+                pos += 1;
+            }
+            continue;
+        }
+        break;
+    }
+
+    let start = pos;
+    while pos < bytes.len() && !bytes[pos].is_ascii_whitespace() {
+        pos += 1;
+    }
+    if start == pos {
+        return Err(LoadError::format(path, "unexpected end of image header"));
+    }
+
+    Ok((&bytes[start..pos], pos))
+}
+
+fn parse_number(token: &[u8], what: &str, path: &Path) -> Result<usize, LoadError> {
+    std::str::from_utf8(token)
+        .ok()
+        .and_then(|t| t.parse::<usize>().ok())
+        .ok_or_else(|| {
+            LoadError::format(
+                path,
+                format!("invalid PPM {} '{}'", what, String::from_utf8_lossy(token)),
+            )
+        })
+}
+
+fn decode_ppm(bytes: &[u8], path: &Path) -> Result<Image, LoadError> {
+    let binary = bytes.starts_with(b"P6");
+    let (_, pos) = next_token(bytes, 0, path)?;
+    let (token, pos) = next_token(bytes, pos, path)?;
+    let width = parse_number(token, "width", path)?;
+    let (token, pos) = next_token(bytes, pos, path)?;
+    let height = parse_number(token, "height", path)?;
+    let (token, mut pos) = next_token(bytes, pos, path)?;
+    let maxval = parse_number(token, "maximum value", path)?;
+
+    if width == 0 || height == 0 {
+        return Err(LoadError::format(path, "PPM image has no pixels"));
+    }
+    if maxval == 0 || maxval > 65535 {
+        return Err(LoadError::format(
+            path,
+            format!("PPM maximum value {} out of range", maxval),
+        ));
+    }
+
+    let count = width
+        .checked_mul(height)
+        .and_then(|n| n.checked_mul(3))
+        .ok_or_else(|| {
+            LoadError::format(path, format!("PPM size {}x{} too large", width, height))
+        })?;
+
+    let samples: Vec<usize> = if binary {
+        // A single whitespace byte separates the header from the raster.
+        pos += 1;
+        let sample_size = if maxval < 256 { 1 } else { 2 };
+        // Checked before anything is allocated, so a header claiming a huge
+        // image fails here rather than exhausting memory.
+        let raster = count
+            .checked_mul(sample_size)
+            .and_then(|len| bytes.get(pos..pos.checked_add(len)?))
+            .ok_or_else(|| {
+                LoadError::format(
+                    path,
+                    format!(
+                        "PPM raster truncated: expected {} bytes, found {}",
+                        count.saturating_mul(sample_size),
+                        bytes.len().saturating_sub(pos)
+                    ),
+                )
+            })?;
+        if sample_size == 1 {
+            raster.iter().map(|&b| b as usize).collect()
+        } else {
+            raster
+                .chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]]) as usize)
+                .collect()
+        }
+    } else {
+        // Every sample takes at least a byte, which bounds what a lying header
+        // can make us reserve.
+        let mut samples = Vec::with_capacity(count.min(bytes.len().saturating_sub(pos)));
+        for _ in 0..count {
+            let (token, next) = next_token(bytes, pos, path)?;
+            samples.push(parse_number(token, "sample", path)?);
+            pos = next;
+        }
+        samples
+    };
+
+    if let Some(bad) = samples.iter().find(|&&s| s > maxval) {
+        return Err(LoadError::format(
+            path,
+            format!("PPM sample {} exceeds maximum value {}", bad, maxval),
+        ));
+    }
+
+    let max = maxval as f64;
+    let pixels = samples
+        .chunks_exact(3)
+        .map(|c| {
+            Vec3::new(
+                to_linear(c[0] as f64, max),
+                to_linear(c[1] as f64, max),
+                to_linear(c[2] as f64, max),
+            )
+        })
+        .collect();
+
+    Ok(Image::new(width, height, pixels))
+}
+
+fn decode_png(bytes: &[u8], path: &Path) -> Result<Image, LoadError> {
+    let png_error = |e: png::DecodingError| LoadError::format(path, format!("invalid PNG: {}", e));
+
+    let mut decoder = png::Decoder::new(bytes);
+    // Expand palettes and low bit depths and strip 16-bit samples so every
+    // image arrives as 8-bit gray or RGB, with or without alpha.
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(png_error)?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).map_err(png_error)?;
+    let buf = &buf[..info.buffer_size()];
+
+    let channels = match info.color_type {
+        png::ColorType::Grayscale => 1,
+        png::ColorType::GrayscaleAlpha => 2,
+        png::ColorType::Rgb => 3,
+        png::ColorType::Rgba => 4,
+        png::ColorType::Indexed => {
+            return Err(LoadError::format(path, "unexpanded indexed PNG"));
+        }
+    };
+
+    let pixels = buf
+        .chunks_exact(channels)
+        .map(|c| {
+            let (r, g, b) = if channels < 3 {
+                (c[0], c[0], c[0])
+            } else {
+                (c[0], c[1], c[2])
+            };
+            Vec3::new(
+                to_linear(r as f64, 255.0),
+                to_linear(g as f64, 255.0),
+                to_linear(b as f64, 255.0),
+            )
+        })
+        .collect();
+
+    Ok(Image::new(
+        info.width as usize,
+        info.height as usize,
+        pixels,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_p3() {
+        let src = b"P3\n# two pixels\n2 1\n255\n255 0 0\n0 0 255\n";
+        let image = parse_image(src, Path::new("a.ppm")).unwrap();
+        assert_eq!(2, image.width());
+        assert_eq!(1, image.height());
+        assert_eq!(Vec3::new(1.0, 0.0, 0.0), *image.pixel(0, 0));
+        assert_eq!(Vec3::new(0.0, 0.0, 1.0), *image.pixel(1, 0));
+    }
+
+    #[test]
+    fn test_parse_p6() {
+        let mut src = b"P6 1 2 255\n".to_vec();
+        src.extend_from_slice(&[0, 255, 0, 255, 255, 255]);
+        let image = parse_image(&src, Path::new("a.ppm")).unwrap();
+        assert_eq!(Vec3::new(0.0, 1.0, 0.0), *image.pixel(0, 0));
+        assert_eq!(Vec3::new(1.0, 1.0, 1.0), *image.pixel(0, 1));
+
+        let err = parse_image(&src[..src.len() - 1], Path::new("a.ppm"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            "a.ppm: PPM raster truncated: expected 6 bytes, found 5",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_ppm_huge_header() {
+        // Sizes that would overflow, or that no real raster could back, fail
+        // cleanly instead of reserving memory up front.
+        let err = parse_image(b"P6 4294967296 4294967296 255\n", Path::new("a.ppm"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            "a.ppm: PPM size 4294967296x4294967296 too large",
+            err.to_string()
+        );
+
+        let err = parse_image(b"P6 100000 100000 255\n\0\0\0", Path::new("a.ppm"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            "a.ppm: PPM raster truncated: expected 30000000000 bytes, found 3",
+            err.to_string()
+        );
+
+        let err = parse_image(b"P3 100000 100000 255\n0 0 0", Path::new("a.ppm"))
+            .err()
+            .unwrap();
+        assert_eq!("a.ppm: unexpected end of image header", err.to_string());
+    }
+
+    #[test]
+    fn test_parse_png() {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut bytes, 2, 1);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            writer.write_image_data(&[255, 0, 0, 0, 255, 0]).unwrap();
+        }
+        let image = parse_image(&bytes, Path::new("a.png")).unwrap();
+        assert_eq!(2, image.width());
+        assert_eq!(Vec3::new(1.0, 0.0, 0.0), *image.pixel(0, 0));
+        assert_eq!(Vec3::new(0.0, 1.0, 0.0), *image.pixel(1, 0));
+
+        let err = parse_image(&bytes[..20], Path::new("a.png")).err().unwrap();
+        assert!(err.to_string().starts_with("a.png: invalid PNG"));
+    }
+
+    #[test]
+    fn test_parse_unknown_format() {
+        let err = parse_image(b"GIF89a", Path::new("a.gif")).err().unwrap();
+        assert_eq!(
+            "a.gif: unrecognized image format (expected PPM, PNG, Radiance HDR or PFM)",
+            err.to_string()
+        );
+    }
+}
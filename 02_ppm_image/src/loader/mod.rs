@@ -0,0 +1,93 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    io,
+    path::{Path, PathBuf},
+};
+
+pub mod hdr;
+pub mod image;
+pub mod mtl;
+pub mod obj;
+pub mod ply;
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    // Malformed data with no meaningful line, e.g. inside a binary payload.
+    Format {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl LoadError {
+    pub fn io(path: &Path, source: io::Error) -> Self {
+        LoadError::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    pub fn parse(path: &Path, line: usize, message: impl Into<String>) -> Self {
+        LoadError::Parse {
+            path: path.to_path_buf(),
+            line,
+            message: message.into(),
+        }
+    }
+
+    pub fn format(path: &Path, message: impl Into<String>) -> Self {
+        LoadError::Format {
+            path: path.to_path_buf(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            LoadError::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+            LoadError::Format { path, message } => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io { source, .. } => Some(source),
+            LoadError::Parse { .. } | LoadError::Format { .. } => None,
+        }
+    }
+}
+
+// Parses the next whitespace-separated token as an f64, naming the field in
+// the error so malformed files point at what was expected.
+pub(crate) fn parse_f64<'a>(
+    tokens: &mut impl Iterator<Item = &'a str>,
+    path: &Path,
+    line: usize,
+    what: &str,
+) -> Result<f64, LoadError> {
+    let token = tokens
+        .next()
+        .ok_or_else(|| LoadError::parse(path, line, format!("missing {}", what)))?;
+    token
+        .parse::<f64>()
+        .map_err(|_| LoadError::parse(path, line, format!("invalid {} '{}'", what, token)))
+}
@@ -0,0 +1,179 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+    sync::Arc,
+};
+
+use crate::{
+    material::{dielectric::Dielectric, lambertian::Lambertian, material::Material, metal::Metal},
+    model::vec3::Vec3,
+};
+
+use super::{parse_f64, LoadError};
+
+pub type MaterialLibrary = HashMap<String, Arc<dyn Material>>;
+
+// The subset of an MTL entry we can map onto our materials.
+struct MtlEntry {
+    kd: Vec3,   // diffuse color
+    ks: Vec3,   // specular color
+    ns: f64,    // specular exponent
+    ni: f64,    // index of refraction
+    d: f64,     // dissolve (1.0 is opaque)
+    illum: u32, // illumination model
+}
+
+impl Default for MtlEntry {
+    fn default() -> Self {
+        Self {
+            kd: Vec3::new(0.8, 0.8, 0.8),
+            ks: Vec3::new(0.0, 0.0, 0.0),
+            ns: 0.0,
+            ni: 1.5,
+            d: 1.0,
+            illum: 2,
+        }
+    }
+}
+
+impl MtlEntry {
+    // Transparent or refractive illumination models become glass, reflective
+    // ones (or entries whose specular color dominates) become metal with a
+    // fuzz derived from the Phong exponent, and everything else is diffuse.
+    fn to_material(&self) -> Arc<dyn Material> {
+        let max = |c: &Vec3| c.x().max(c.y()).max(c.z());
+
+        if self.d < 1.0 || matches!(self.illum, 4 | 6 | 7 | 9) {
+            Arc::new(Dielectric::new(self.ni))
+        } else if matches!(self.illum, 3 | 5) || max(&self.ks) > max(&self.kd) {
+            let fuzz = (2.0 / (self.ns + 2.0)).sqrt();
+            Arc::new(Metal::new(&self.ks, fuzz))
+        } else {
+            Arc::new(Lambertian::new(&self.kd))
+        }
+    }
+}
+
+pub fn load_mtl(path: &Path) -> Result<MaterialLibrary, LoadError> {
+    let file = File::open(path).map_err(|e| LoadError::io(path, e))?;
+    parse_mtl(BufReader::new(file), path)
+}
+
+pub fn parse_mtl<R: BufRead>(reader: R, path: &Path) -> Result<MaterialLibrary, LoadError> {
+    let mut library = MaterialLibrary::new();
+    let mut current: Option<(String, MtlEntry)> = None;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.map_err(|e| LoadError::io(path, e))?;
+        let mut tokens = line.split_whitespace();
+
+        let keyword = match tokens.next() {
+            Some(k) if !k.starts_with('#') => k,
+            _ => continue,
+        };
+
+        if keyword == "newmtl" {
+            let name = tokens.collect::<Vec<_>>().join(" ");
+            if name.is_empty() {
+                return Err(LoadError::parse(path, line_no, "newmtl without a name"));
+            }
+            if let Some((name, entry)) = current.take() {
+                library.insert(name, entry.to_material());
+            }
+            current = Some((name, MtlEntry::default()));
+            continue;
+        }
+
+        let entry = match current.as_mut() {
+            Some((_, entry)) => entry,
+            None => {
+                return Err(LoadError::parse(
+                    path,
+                    line_no,
+                    format!("'{}' before any newmtl", keyword),
+                ))
+            }
+        };
+
+        match keyword {
+            "Kd" | "Ks" => {
+                let r = parse_f64(&mut tokens, path, line_no, "red component")?;
+                let g = parse_f64(&mut tokens, path, line_no, "green component")?;
+                let b = parse_f64(&mut tokens, path, line_no, "blue component")?;
+                if keyword == "Kd" {
+                    entry.kd = Vec3::new(r, g, b);
+                } else {
+                    entry.ks = Vec3::new(r, g, b);
+                }
+            }
+            "Ns" => entry.ns = parse_f64(&mut tokens, path, line_no, "specular exponent")?,
+            "Ni" => entry.ni = parse_f64(&mut tokens, path, line_no, "index of refraction")?,
+            "d" => entry.d = parse_f64(&mut tokens, path, line_no, "dissolve")?,
+            "Tr" => entry.d = 1.0 - parse_f64(&mut tokens, path, line_no, "transparency")?,
+            "illum" => {
+                let token = tokens
+                    .next()
+                    .ok_or_else(|| LoadError::parse(path, line_no, "missing illumination model"))?;
+                entry.illum = token.parse().map_err(|_| {
+                    LoadError::parse(
+                        path,
+                        line_no,
+                        format!("invalid illumination model '{}'", token),
+                    )
+                })?;
+            }
+            // Ambient, emissive and texture-map statements have no equivalent yet.
+            _ => {}
+        }
+    }
+
+    if let Some((name, entry)) = current.take() {
+        library.insert(name, entry.to_material());
+    }
+
+    Ok(library)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mtl() {
+        let src = "# materials\n\
+                   newmtl red\n\
+                   Kd 0.8 0.1 0.1\n\
+                   newmtl chrome\n\
+                   Kd 0.0 0.0 0.0\n\
+                   Ks 0.9 0.9 0.9\n\
+                   Ns 1000\n\
+                   newmtl glass\n\
+                   Ni 1.45\n\
+                   illum 7\n";
+        let library = parse_mtl(src.as_bytes(), Path::new("test.mtl")).unwrap();
+        assert_eq!(3, library.len());
+        assert!(library.contains_key("red"));
+        assert!(library.contains_key("chrome"));
+        assert!(library.contains_key("glass"));
+    }
+
+    #[test]
+    fn test_parse_mtl_reports_line() {
+        let src = "newmtl red\nKd 0.8 oops 0.1\n";
+        let err = parse_mtl(src.as_bytes(), Path::new("test.mtl"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            "test.mtl:2: invalid green component 'oops'",
+            err.to_string()
+        );
+
+        let err = parse_mtl("Kd 1 1 1\n".as_bytes(), Path::new("test.mtl"))
+            .err()
+            .unwrap();
+        assert_eq!("test.mtl:1: 'Kd' before any newmtl", err.to_string());
+    }
+}
@@ -0,0 +1,303 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+    sync::Arc,
+};
+
+use crate::{
+    material::material::Material,
+    model::{mesh::Mesh, triangle::Triangle, vec3::Vec3},
+};
+
+use super::{
+    mtl::{load_mtl, MaterialLibrary},
+    parse_f64, LoadError,
+};
+
+use Vec3 as Point3;
+
+// Indices into the position/texcoord/normal arrays for one face corner.
+#[derive(Copy, Clone)]
+struct FaceVertex {
+    v: usize,
+    vt: Option<usize>,
+    vn: Option<usize>,
+}
+
+pub fn load_obj(path: &Path, default_material: Arc<dyn Material>) -> Result<Mesh, LoadError> {
+    let file = File::open(path).map_err(|e| LoadError::io(path, e))?;
+    parse_obj(BufReader::new(file), path, default_material)
+}
+
+// Faces without a usemtl (or before the first one) get default_material.
+// mtllib paths are resolved relative to the directory containing `path`.
+pub fn parse_obj<R: BufRead>(
+    reader: R,
+    path: &Path,
+    default_material: Arc<dyn Material>,
+) -> Result<Mesh, LoadError> {
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut positions: Vec<Point3> = Vec::new();
+    let mut texcoords: Vec<(f64, f64)> = Vec::new();
+    let mut normals: Vec<Vec3> = Vec::new();
+    let mut library = MaterialLibrary::new();
+    let mut material = default_material;
+    let mut triangles = Vec::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.map_err(|e| LoadError::io(path, e))?;
+        let mut tokens = line.split_whitespace();
+
+        let keyword = match tokens.next() {
+            Some(k) if !k.starts_with('#') => k,
+            _ => continue,
+        };
+
+        match keyword {
+            "v" => {
+                let x = parse_f64(&mut tokens, path, line_no, "vertex x")?;
+                let y = parse_f64(&mut tokens, path, line_no, "vertex y")?;
+                let z = parse_f64(&mut tokens, path, line_no, "vertex z")?;
+                positions.push(Point3::new(x, y, z));
+            }
+            "vt" => {
+                let u = parse_f64(&mut tokens, path, line_no, "texture u")?;
+                // v is optional for 1D textures.
+                let mut rest = tokens.peekable();
+                let v = match rest.peek() {
+                    Some(_) => parse_f64(&mut rest, path, line_no, "texture v")?,
+                    None => 0.0,
+                };
+                texcoords.push((u, v));
+            }
+            "vn" => {
+                let x = parse_f64(&mut tokens, path, line_no, "normal x")?;
+                let y = parse_f64(&mut tokens, path, line_no, "normal y")?;
+                let z = parse_f64(&mut tokens, path, line_no, "normal z")?;
+                normals.push(Vec3::new(x, y, z).unit_vector());
+            }
+            "f" => {
+                let corners = tokens
+                    .map(|t| {
+                        parse_face_vertex(
+                            t,
+                            positions.len(),
+                            texcoords.len(),
+                            normals.len(),
+                            path,
+                            line_no,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if corners.len() < 3 {
+                    return Err(LoadError::parse(
+                        path,
+                        line_no,
+                        format!("face needs at least 3 vertices, found {}", corners.len()),
+                    ));
+                }
+
+                // Fan-triangulate polygons around their first corner.
+                for i in 1..corners.len() - 1 {
+                    let tri = [corners[0], corners[i], corners[i + 1]];
+                    triangles.push(make_triangle(
+                        &tri,
+                        &positions,
+                        &texcoords,
+                        &normals,
+                        material.clone(),
+                    ));
+                }
+            }
+            "mtllib" => {
+                for name in tokens {
+                    library.extend(load_mtl(&base_dir.join(name))?);
+                }
+            }
+            "usemtl" => {
+                let name = tokens.collect::<Vec<_>>().join(" ");
+                material = library.get(&name).cloned().ok_or_else(|| {
+                    LoadError::parse(path, line_no, format!("unknown material '{}'", name))
+                })?;
+            }
+            // Groups, objects, smoothing groups and free-form geometry don't
+            // affect the triangles we build.
+            _ => {}
+        }
+    }
+
+    if triangles.is_empty() {
+        return Err(LoadError::format(path, "no faces"));
+    }
+    Ok(Mesh::new(triangles))
+}
+
+fn parse_index(
+    token: &str,
+    count: usize,
+    what: &str,
+    path: &Path,
+    line: usize,
+) -> Result<usize, LoadError> {
+    let index: i64 = token
+        .parse()
+        .map_err(|_| LoadError::parse(path, line, format!("invalid {} index '{}'", what, token)))?;
+
+    // OBJ indices are 1-based; negative values count back from the last
+    // element defined so far.
+    let resolved = if index > 0 {
+        index - 1
+    } else {
+        count as i64 + index
+    };
+
+    if index == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(LoadError::parse(
+            path,
+            line,
+            format!("{} index {} out of range ({} defined)", what, index, count),
+        ));
+    }
+
+    Ok(resolved as usize)
+}
+
+fn parse_face_vertex(
+    token: &str,
+    position_count: usize,
+    texcoord_count: usize,
+    normal_count: usize,
+    path: &Path,
+    line: usize,
+) -> Result<FaceVertex, LoadError> {
+    let mut parts = token.split('/');
+
+    let v = parse_index(
+        parts.next().unwrap_or(""),
+        position_count,
+        "vertex",
+        path,
+        line,
+    )?;
+    let vt = match parts.next() {
+        Some("") | None => None,
+        Some(t) => Some(parse_index(t, texcoord_count, "texture", path, line)?),
+    };
+    let vn = match parts.next() {
+        Some("") | None => None,
+        Some(t) => Some(parse_index(t, normal_count, "normal", path, line)?),
+    };
+
+    if parts.next().is_some() {
+        return Err(LoadError::parse(
+            path,
+            line,
+            format!("malformed face vertex '{}'", token),
+        ));
+    }
+
+    Ok(FaceVertex { v, vt, vn })
+}
+
+fn make_triangle(
+    corners: &[FaceVertex; 3],
+    positions: &[Point3],
+    texcoords: &[(f64, f64)],
+    normals: &[Vec3],
+    material: Arc<dyn Material>,
+) -> Triangle {
+    let [a, b, c] = corners;
+    let mut triangle = Triangle::new(positions[a.v], positions[b.v], positions[c.v], material);
+
+    // Per-vertex attributes are only usable if every corner supplies them.
+    if let (Some(na), Some(nb), Some(nc)) = (a.vn, b.vn, c.vn) {
+        triangle = triangle.with_normals(normals[na], normals[nb], normals[nc]);
+    }
+    if let (Some(ta), Some(tb), Some(tc)) = (a.vt, b.vt, c.vt) {
+        triangle = triangle.with_uvs(texcoords[ta], texcoords[tb], texcoords[tc]);
+    }
+
+    triangle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        material::lambertian::Lambertian,
+        model::{
+            hit::{HitRecord, Hittable},
+            ray::Ray,
+        },
+        sampler::independent::IndependentSampler,
+    };
+
+    fn material() -> Arc<dyn Material> {
+        Arc::new(Lambertian::new(&Vec3::new(0.5, 0.5, 0.5)))
+    }
+
+    fn parse(src: &str) -> Result<Mesh, LoadError> {
+        parse_obj(src.as_bytes(), Path::new("test.obj"), material())
+    }
+
+    #[test]
+    fn test_parse_obj_quad() {
+        let mut sampler = IndependentSampler::new(0);
+        let src = "# unit quad\n\
+                   v 0 0 0\n\
+                   v 1 0 0\n\
+                   v 1 1 0\n\
+                   v 0 1 0\n\
+                   vt 0 0\n\
+                   vt 1 0\n\
+                   vt 1 1\n\
+                   vt 0 1\n\
+                   vn 0 0 1\n\
+                   f 1/1/1 2/2/1 3/3/1 4/4/1\n";
+        let mesh = parse(src).unwrap();
+        assert_eq!(2, mesh.len());
+
+        let r = Ray::new(
+            &Point3::new(0.75, 0.25, 1.0),
+            &Vec3::new(0.0, 0.0, -1.0),
+            0.0,
+        );
+        let mut rec = HitRecord::default();
+        assert!(mesh.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+        assert!((rec.u - 0.75).abs() < 1e-12);
+        assert!((rec.v - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_parse_obj_negative_indices() {
+        let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf -3 -2 -1\n";
+        assert_eq!(1, parse(src).unwrap().len());
+    }
+
+    #[test]
+    fn test_parse_obj_errors() {
+        let err = parse("v 0 0 0\nv 1 0\n").err().unwrap();
+        assert_eq!("test.obj:2: missing vertex z", err.to_string());
+
+        let err = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 4\n").err().unwrap();
+        assert_eq!(
+            "test.obj:4: vertex index 4 out of range (3 defined)",
+            err.to_string()
+        );
+
+        let err = parse("v 0 0 0\nv 1 0 0\nf 1 2\n").err().unwrap();
+        assert_eq!(
+            "test.obj:3: face needs at least 3 vertices, found 2",
+            err.to_string()
+        );
+
+        let err = parse("usemtl missing\n").err().unwrap();
+        assert_eq!("test.obj:1: unknown material 'missing'", err.to_string());
+
+        let err = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\n").err().unwrap();
+        assert_eq!("test.obj: no faces", err.to_string());
+    }
+}
@@ -0,0 +1,679 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+    sync::Arc,
+};
+
+use crate::{
+    material::{lambertian::Lambertian, material::Material},
+    model::{mesh::Mesh, triangle::Triangle, vec3::Vec3},
+    texture::vertex_color::VertexColorTexture,
+};
+
+use super::LoadError;
+
+use Vec3 as Point3;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum ScalarType {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+impl ScalarType {
+    fn from_name(name: &str) -> Option<ScalarType> {
+        match name {
+            "char" | "int8" => Some(ScalarType::Int8),
+            "uchar" | "uint8" => Some(ScalarType::UInt8),
+            "short" | "int16" => Some(ScalarType::Int16),
+            "ushort" | "uint16" => Some(ScalarType::UInt16),
+            "int" | "int32" => Some(ScalarType::Int32),
+            "uint" | "uint32" => Some(ScalarType::UInt32),
+            "float" | "float32" => Some(ScalarType::Float32),
+            "double" | "float64" => Some(ScalarType::Float64),
+            _ => None,
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            ScalarType::Int8 | ScalarType::UInt8 => 1,
+            ScalarType::Int16 | ScalarType::UInt16 => 2,
+            ScalarType::Int32 | ScalarType::UInt32 | ScalarType::Float32 => 4,
+            ScalarType::Float64 => 8,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum PropertyKind {
+    Scalar(ScalarType),
+    List(ScalarType, ScalarType), // (count type, item type)
+}
+
+#[derive(Debug)]
+struct Property {
+    name: String,
+    kind: PropertyKind,
+}
+
+#[derive(Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+// Vertex and face data read from a PLY file. Polygons are fan-triangulated;
+// normals, colors and texture coordinates are kept only if the file has them.
+pub struct PlyData {
+    pub positions: Vec<Point3>,
+    pub normals: Option<Vec<Vec3>>,
+    pub colors: Option<Vec<Vec3>>, // linear [0,1] RGB
+    pub uvs: Option<Vec<(f64, f64)>>,
+    pub faces: Vec<[usize; 3]>,
+}
+
+impl PlyData {
+    pub fn to_mesh(&self, material: Arc<dyn Material>) -> Mesh {
+        Mesh::new(
+            self.faces
+                .iter()
+                .map(|face| self.triangle(face, material.clone()))
+                .collect(),
+        )
+    }
+
+    // Uses the vertex colors, interpolated across each face, as the albedo of
+    // one Lambertian shared by the whole mesh. The faces' texture coordinates
+    // are taken over to carry the interpolation. Falls back to `material` if
+    // the file has no colors.
+    pub fn to_vertex_color_mesh(&self, material: Arc<dyn Material>) -> Mesh {
+        let colors = match &self.colors {
+            Some(colors) => colors,
+            None => return self.to_mesh(material),
+        };
+
+        let texture = VertexColorTexture::new(
+            self.faces
+                .iter()
+                .map(|face| face.map(|i| colors[i]))
+                .collect(),
+        );
+        let material: Arc<dyn Material> = Arc::new(Lambertian::with_texture(Arc::new(texture)));
+        Mesh::new(
+            self.faces
+                .iter()
+                .enumerate()
+                .map(|(i, face)| {
+                    let [uv0, uv1, uv2] = VertexColorTexture::face_uvs(i);
+                    self.triangle(face, material.clone())
+                        .with_uvs(uv0, uv1, uv2)
+                })
+                .collect(),
+        )
+    }
+
+    fn triangle(&self, face: &[usize; 3], material: Arc<dyn Material>) -> Triangle {
+        let [a, b, c] = *face;
+        let mut triangle = Triangle::new(
+            self.positions[a],
+            self.positions[b],
+            self.positions[c],
+            material,
+        );
+        if let Some(normals) = &self.normals {
+            triangle = triangle.with_normals(normals[a], normals[b], normals[c]);
+        }
+        if let Some(uvs) = &self.uvs {
+            triangle = triangle.with_uvs(uvs[a], uvs[b], uvs[c]);
+        }
+        triangle
+    }
+}
+
+// Loads a PLY mesh, using its vertex colors as albedo when present and
+// `material` otherwise.
+pub fn load_ply(path: &Path, material: Arc<dyn Material>) -> Result<Mesh, LoadError> {
+    let data = read_ply(path)?;
+    if data.faces.is_empty() {
+        return Err(LoadError::format(path, "no faces"));
+    }
+    Ok(data.to_vertex_color_mesh(material))
+}
+
+pub fn read_ply(path: &Path) -> Result<PlyData, LoadError> {
+    let file = File::open(path).map_err(|e| LoadError::io(path, e))?;
+    parse_ply(BufReader::new(file), path)
+}
+
+pub fn parse_ply<R: BufRead>(mut reader: R, path: &Path) -> Result<PlyData, LoadError> {
+    let (format, elements, header_lines) = parse_header(&mut reader, path)?;
+
+    let mut source: Box<dyn ValueSource> = match format {
+        Format::Ascii => Box::new(AsciiSource {
+            reader,
+            path,
+            line: header_lines,
+            tokens: Vec::new(),
+            next_token: 0,
+        }),
+        Format::BinaryLittleEndian => Box::new(BinarySource { reader, path }),
+    };
+
+    let mut data = PlyData {
+        positions: Vec::new(),
+        normals: None,
+        colors: None,
+        uvs: None,
+        faces: Vec::new(),
+    };
+
+    for element in elements.iter() {
+        match element.name.as_str() {
+            "vertex" => read_vertices(element, source.as_mut(), &mut data)?,
+            "face" => read_faces(element, source.as_mut(), &mut data, path)?,
+            _ => {
+                for _ in 0..element.count {
+                    source.begin_instance()?;
+                    for property in element.properties.iter() {
+                        read_property(property, source.as_mut())?;
+                    }
+                }
+            }
+        }
+    }
+
+    let vertex_count = data.positions.len();
+    if let Some(face) = data
+        .faces
+        .iter()
+        .find(|f| f.iter().any(|&i| i >= vertex_count))
+    {
+        return Err(LoadError::format(
+            path,
+            format!(
+                "face references vertex {:?} but only {} vertices are defined",
+                face, vertex_count
+            ),
+        ));
+    }
+
+    Ok(data)
+}
+
+fn parse_header<R: BufRead>(
+    reader: &mut R,
+    path: &Path,
+) -> Result<(Format, Vec<Element>, usize), LoadError> {
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut line_no = 0;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        line_no += 1;
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|e| LoadError::io(path, e))?;
+        if read == 0 {
+            return Err(LoadError::parse(path, line_no, "missing end_header"));
+        }
+
+        let mut tokens = line.split_whitespace();
+        let keyword = tokens.next().unwrap_or("");
+
+        if line_no == 1 {
+            if keyword != "ply" {
+                return Err(LoadError::parse(path, line_no, "not a PLY file"));
+            }
+            continue;
+        }
+
+        match keyword {
+            "format" => {
+                format = Some(match tokens.next() {
+                    Some("ascii") => Format::Ascii,
+                    Some("binary_little_endian") => Format::BinaryLittleEndian,
+                    Some(other) => {
+                        return Err(LoadError::parse(
+                            path,
+                            line_no,
+                            format!("unsupported format '{}'", other),
+                        ))
+                    }
+                    None => return Err(LoadError::parse(path, line_no, "missing format")),
+                });
+            }
+            "element" => {
+                let name = tokens
+                    .next()
+                    .ok_or_else(|| LoadError::parse(path, line_no, "missing element name"))?;
+                let count = tokens
+                    .next()
+                    .and_then(|t| t.parse::<usize>().ok())
+                    .ok_or_else(|| LoadError::parse(path, line_no, "invalid element count"))?;
+                elements.push(Element {
+                    name: name.to_string(),
+                    count,
+                    properties: Vec::new(),
+                });
+            }
+            "property" => {
+                let element = elements.last_mut().ok_or_else(|| {
+                    LoadError::parse(path, line_no, "property before any element")
+                })?;
+                let parse_type = |name: Option<&str>| {
+                    let name = name.unwrap_or("");
+                    ScalarType::from_name(name).ok_or_else(|| {
+                        LoadError::parse(path, line_no, format!("unknown property type '{}'", name))
+                    })
+                };
+
+                let first = tokens.next();
+                let kind = if first == Some("list") {
+                    let count_type = parse_type(tokens.next())?;
+                    let item_type = parse_type(tokens.next())?;
+                    PropertyKind::List(count_type, item_type)
+                } else {
+                    PropertyKind::Scalar(parse_type(first)?)
+                };
+                let name = tokens
+                    .next()
+                    .ok_or_else(|| LoadError::parse(path, line_no, "missing property name"))?;
+                element.properties.push(Property {
+                    name: name.to_string(),
+                    kind,
+                });
+            }
+            "end_header" => break,
+            "comment" | "obj_info" | "" => {}
+            other => {
+                return Err(LoadError::parse(
+                    path,
+                    line_no,
+                    format!("unexpected header keyword '{}'", other),
+                ))
+            }
+        }
+    }
+
+    let format =
+        format.ok_or_else(|| LoadError::parse(path, line_no, "header has no format line"))?;
+    Ok((format, elements, line_no))
+}
+
+trait ValueSource {
+    // Called before each element instance; ASCII files hold one per line.
+    fn begin_instance(&mut self) -> Result<(), LoadError>;
+    fn read(&mut self, ty: ScalarType) -> Result<f64, LoadError>;
+}
+
+struct AsciiSource<'a, R> {
+    reader: R,
+    path: &'a Path,
+    line: usize,
+    tokens: Vec<String>,
+    next_token: usize,
+}
+
+impl<R: BufRead> ValueSource for AsciiSource<'_, R> {
+    fn begin_instance(&mut self) -> Result<(), LoadError> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            self.line += 1;
+            let read = self
+                .reader
+                .read_line(&mut line)
+                .map_err(|e| LoadError::io(self.path, e))?;
+            if read == 0 {
+                return Err(LoadError::parse(
+                    self.path,
+                    self.line,
+                    "unexpected end of file",
+                ));
+            }
+            if !line.trim().is_empty() {
+                break;
+            }
+        }
+
+        self.tokens = line.split_whitespace().map(|t| t.to_string()).collect();
+        self.next_token = 0;
+        Ok(())
+    }
+
+    fn read(&mut self, _ty: ScalarType) -> Result<f64, LoadError> {
+        let token = self
+            .tokens
+            .get(self.next_token)
+            .ok_or_else(|| LoadError::parse(self.path, self.line, "too few values"))?;
+        self.next_token += 1;
+        token.parse::<f64>().map_err(|_| {
+            LoadError::parse(self.path, self.line, format!("invalid value '{}'", token))
+        })
+    }
+}
+
+struct BinarySource<'a, R> {
+    reader: R,
+    path: &'a Path,
+}
+
+impl<R: Read> ValueSource for BinarySource<'_, R> {
+    fn begin_instance(&mut self) -> Result<(), LoadError> {
+        Ok(())
+    }
+
+    fn read(&mut self, ty: ScalarType) -> Result<f64, LoadError> {
+        let mut buf = [0u8; 8];
+        let bytes = &mut buf[..ty.size()];
+        self.reader.read_exact(bytes).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                LoadError::format(self.path, "unexpected end of binary data")
+            } else {
+                LoadError::io(self.path, e)
+            }
+        })?;
+
+        Ok(match ty {
+            ScalarType::Int8 => bytes[0] as i8 as f64,
+            ScalarType::UInt8 => bytes[0] as f64,
+            ScalarType::Int16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+            ScalarType::UInt16 => u16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+            ScalarType::Int32 => i32::from_le_bytes(buf[..4].try_into().unwrap()) as f64,
+            ScalarType::UInt32 => u32::from_le_bytes(buf[..4].try_into().unwrap()) as f64,
+            ScalarType::Float32 => f32::from_le_bytes(buf[..4].try_into().unwrap()) as f64,
+            ScalarType::Float64 => f64::from_le_bytes(buf),
+        })
+    }
+}
+
+fn read_property(property: &Property, source: &mut dyn ValueSource) -> Result<Vec<f64>, LoadError> {
+    match property.kind {
+        PropertyKind::Scalar(ty) => Ok(vec![source.read(ty)?]),
+        PropertyKind::List(count_type, item_type) => {
+            let count = source.read(count_type)? as usize;
+            (0..count).map(|_| source.read(item_type)).collect()
+        }
+    }
+}
+
+fn read_vertices(
+    element: &Element,
+    source: &mut dyn ValueSource,
+    data: &mut PlyData,
+) -> Result<(), LoadError> {
+    let find = |names: &[&str]| {
+        element
+            .properties
+            .iter()
+            .position(|p| names.contains(&p.name.as_str()))
+    };
+    let xyz = [find(&["x"]), find(&["y"]), find(&["z"])];
+    let normal = [find(&["nx"]), find(&["ny"]), find(&["nz"])];
+    let color = [
+        find(&["red", "r", "diffuse_red"]),
+        find(&["green", "g", "diffuse_green"]),
+        find(&["blue", "b", "diffuse_blue"]),
+    ];
+    let uv = [
+        find(&["u", "s", "texture_u", "texture_s"]),
+        find(&["v", "t", "texture_v", "texture_t"]),
+    ];
+
+    let has_normals = normal.iter().all(Option::is_some);
+    let has_colors = color.iter().all(Option::is_some);
+    let has_uvs = uv.iter().all(Option::is_some);
+
+    let mut normals = Vec::new();
+    let mut colors = Vec::new();
+    let mut uvs = Vec::new();
+
+    // Integer color channels are 0..=255; floating point ones are already 0..1.
+    let color_scale: Vec<f64> = color
+        .iter()
+        .map(|i| match i.map(|i| &element.properties[i].kind) {
+            Some(PropertyKind::Scalar(ScalarType::Float32 | ScalarType::Float64)) => 1.0,
+            Some(PropertyKind::Scalar(ScalarType::UInt16)) => 1.0 / 65535.0,
+            _ => 1.0 / 255.0,
+        })
+        .collect();
+
+    for _ in 0..element.count {
+        source.begin_instance()?;
+        let mut values = Vec::with_capacity(element.properties.len());
+        for property in element.properties.iter() {
+            values.push(
+                read_property(property, source)?
+                    .first()
+                    .copied()
+                    .unwrap_or(0.0),
+            );
+        }
+
+        let get = |i: Option<usize>| i.map(|i| values[i]).unwrap_or(0.0);
+        data.positions
+            .push(Point3::new(get(xyz[0]), get(xyz[1]), get(xyz[2])));
+        if has_normals {
+            normals.push(Vec3::new(get(normal[0]), get(normal[1]), get(normal[2])).unit_vector());
+        }
+        if has_colors {
+            colors.push(Vec3::new(
+                get(color[0]) * color_scale[0],
+                get(color[1]) * color_scale[1],
+                get(color[2]) * color_scale[2],
+            ));
+        }
+        if has_uvs {
+            uvs.push((get(uv[0]), get(uv[1])));
+        }
+    }
+
+    data.normals = has_normals.then_some(normals);
+    data.colors = has_colors.then_some(colors);
+    data.uvs = has_uvs.then_some(uvs);
+    Ok(())
+}
+
+fn read_faces(
+    element: &Element,
+    source: &mut dyn ValueSource,
+    data: &mut PlyData,
+    path: &Path,
+) -> Result<(), LoadError> {
+    let indices = element
+        .properties
+        .iter()
+        .position(|p| p.name == "vertex_indices" || p.name == "vertex_index");
+
+    for _ in 0..element.count {
+        source.begin_instance()?;
+        let mut polygon = Vec::new();
+        for (i, property) in element.properties.iter().enumerate() {
+            let values = read_property(property, source)?;
+            if Some(i) == indices {
+                polygon = values
+                    .into_iter()
+                    .map(|index| {
+                        // Float-typed lists are legal PLY, but only whole,
+                        // non-negative values name a vertex.
+                        if index >= 0.0 && index.fract() == 0.0 {
+                            Ok(index as usize)
+                        } else {
+                            Err(LoadError::format(
+                                path,
+                                format!("invalid vertex index {}", index),
+                            ))
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+            }
+        }
+
+        // Fan-triangulate polygons around their first corner; anything with
+        // fewer than three corners contributes no triangles.
+        for i in 1..polygon.len().saturating_sub(1) {
+            data.faces.push([polygon[0], polygon[i], polygon[i + 1]]);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        material::material::ScatterRecord,
+        model::{
+            hit::{HitRecord, Hittable},
+            ray::Ray,
+        },
+        sampler::independent::IndependentSampler,
+    };
+
+    const ASCII_QUAD: &str = "ply\n\
+                              format ascii 1.0\n\
+                              comment unit quad\n\
+                              element vertex 4\n\
+                              property float x\n\
+                              property float y\n\
+                              property float z\n\
+                              property uchar red\n\
+                              property uchar green\n\
+                              property uchar blue\n\
+                              element face 1\n\
+                              property list uchar int vertex_indices\n\
+                              end_header\n\
+                              0 0 0 255 0 0\n\
+                              1 0 0 255 0 0\n\
+                              1 1 0 0 0 255\n\
+                              0 1 0 0 0 255\n\
+                              4 0 1 2 3\n";
+
+    #[test]
+    fn test_parse_ply_ascii() {
+        let data = parse_ply(ASCII_QUAD.as_bytes(), Path::new("quad.ply")).unwrap();
+        assert_eq!(4, data.positions.len());
+        assert_eq!(Point3::new(1.0, 1.0, 0.0), data.positions[2]);
+        assert!(data.normals.is_none());
+        assert_eq!(Vec3::new(1.0, 0.0, 0.0), data.colors.as_ref().unwrap()[0]);
+        assert_eq!(vec![[0, 1, 2], [0, 2, 3]], data.faces);
+    }
+
+    #[test]
+    fn test_parse_ply_binary_little_endian() {
+        let mut bytes = b"ply\n\
+                          format binary_little_endian 1.0\n\
+                          element vertex 3\n\
+                          property float x\n\
+                          property float y\n\
+                          property float z\n\
+                          property float nx\n\
+                          property float ny\n\
+                          property float nz\n\
+                          element face 1\n\
+                          property list uchar uint vertex_indices\n\
+                          end_header\n"
+            .to_vec();
+        for p in [[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]] {
+            for c in p.iter().chain([0.0f32, 0.0, 1.0].iter()) {
+                bytes.extend_from_slice(&c.to_le_bytes());
+            }
+        }
+        bytes.push(3);
+        for i in [0u32, 1, 2] {
+            bytes.extend_from_slice(&i.to_le_bytes());
+        }
+
+        let data = parse_ply(&bytes[..], Path::new("tri.ply")).unwrap();
+        assert_eq!(3, data.positions.len());
+        assert_eq!(Point3::new(0.0, 1.0, 0.0), data.positions[2]);
+        assert_eq!(Vec3::new(0.0, 0.0, 1.0), data.normals.as_ref().unwrap()[1]);
+        assert_eq!(vec![[0, 1, 2]], data.faces);
+
+        let truncated = &bytes[..bytes.len() - 2];
+        let err = parse_ply(truncated, Path::new("tri.ply")).err().unwrap();
+        assert_eq!("tri.ply: unexpected end of binary data", err.to_string());
+    }
+
+    #[test]
+    fn test_parse_ply_errors() {
+        let err = parse_ply(
+            "ply\nformat binary_big_endian 1.0\n".as_bytes(),
+            Path::new("a.ply"),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            "a.ply:2: unsupported format 'binary_big_endian'",
+            err.to_string()
+        );
+
+        let src = ASCII_QUAD.replace("1 1 0 0 0 255", "1 1 0 0 0");
+        let err = parse_ply(src.as_bytes(), Path::new("quad.ply"))
+            .err()
+            .unwrap();
+        assert_eq!("quad.ply:16: too few values", err.to_string());
+
+        let src = ASCII_QUAD.replace("4 0 1 2 3", "3 0 1 7");
+        let err = parse_ply(src.as_bytes(), Path::new("quad.ply"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            "quad.ply: face references vertex [0, 1, 7] but only 4 vertices are defined",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_ply_rejects_bad_indices() {
+        for (face, index) in [("3 0 -1 2", "-1"), ("3 0 1.7 2", "1.7")] {
+            let src = ASCII_QUAD.replace("4 0 1 2 3", face);
+            let err = parse_ply(src.as_bytes(), Path::new("quad.ply"))
+                .err()
+                .unwrap();
+            assert_eq!(
+                format!("quad.ply: invalid vertex index {}", index),
+                err.to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn test_vertex_color_mesh() {
+        let mut sampler = IndependentSampler::new(0);
+        let data = parse_ply(ASCII_QUAD.as_bytes(), Path::new("quad.ply")).unwrap();
+        let fallback: Arc<dyn Material> = Arc::new(Lambertian::new(&Vec3::new(0.5, 0.5, 0.5)));
+        let mesh = data.to_vertex_color_mesh(fallback);
+        assert_eq!(2, mesh.len());
+
+        // Red along y = 0 and blue along y = 1, blended in between.
+        let r = Ray::new(
+            &Point3::new(0.25, 0.75, 1.0),
+            &Vec3::new(0.0, 0.0, -1.0),
+            0.0,
+        );
+        let mut rec = HitRecord::default();
+        assert!(mesh.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+        let mut srec = ScatterRecord::default();
+        assert!(rec.material.sample(&r, &rec, &mut srec, &mut sampler));
+        assert!((srec.attenuation - Vec3::new(0.25, 0.0, 0.75)).length() < 1e-12);
+    }
+}
@@ -1,29 +1,171 @@
 use std::{
-    io::{self, Write},
+    io::{self, BufWriter, Write},
+    path::Path,
+    process,
     sync::Arc,
+    thread,
 };
 
-use material::material::Material;
-use model::{
-    hit::{HitRecord, Hittable},
-    ray::Ray,
-    vec3::Vec3,
-};
+use ppm_image::material::material::Material;
+use ppm_image::model::vec3::Vec3;
 use Vec3 as Point3;
 
-use util::{
-    rtweekend::INFINITY,
-    rtweekend::{random_double, random_double_by_range},
+use ppm_image::{
+    background::{
+        environment::EnvironmentMap, gradient::GradientBackground, solid::SolidBackground,
+    },
+    filter::{
+        box_filter::BoxFilter, gaussian::GaussianFilter, lanczos::LanczosFilter,
+        mitchell::MitchellFilter, tent::TentFilter, Filter,
+    },
+    integrator::{
+        ambient_occlusion::AmbientOcclusion, path::PathTracer, whitted::WhittedTracer, Integrator,
+    },
+    material::{
+        dielectric::Dielectric, diffuse_light::DiffuseLight, lambertian::Lambertian, metal::Metal,
+    },
+    model::{
+        bvh::BvhNode, camera::Camera, constant_medium::ConstantMedium, hit::HittableList,
+        moving_sphere::MovingSphere, quad::Quad, sphere::Sphere,
+    },
+    renderer::{render, RenderSettings},
+    sampler::{
+        halton::HaltonSampler, independent::IndependentSampler, sobol::SobolSampler,
+        stratified::StratifiedSampler, Sampler,
+    },
+    scene::Scene,
+    texture::noise::MarbleTexture,
+    writer::image::{save_image, write_image, ImageFormat},
 };
 
-use crate::{
-    material::{dielectric::Dielectric, lambertian::Lambertian, metal::Metal},
-    model::{camera::Camera, color::Color, hit::HittableList, sphere::Sphere},
-    util::rtweekend::PI,
-};
-mod material;
-mod model;
-mod util;
+const USAGE: &str = "usage: ppm_image [random|simple_light] [--integrator path|whitted|ao] \
+                     [--min-bounces N] [--max-depth N] [--ao-distance D] \
+                     [--sampler independent|stratified|halton|sobol] \
+                     [--filter box|tent|gaussian|mitchell|lanczos] [--filter-radius PIXELS] \
+                     [--threads N] [--seed N] [--haze DENSITY] [--output FILE.ppm|png|hdr|pfm] \
+                     [--env FILE] [--env-rotation DEGREES] [--env-intensity K]";
+
+// Settings taken from the command line.
+struct Options {
+    scene: String,
+    integrator: String,
+    // Bounces every path makes before Russian roulette may end it.
+    min_bounces: usize,
+    // Recursion limit for the Whitted tracer.
+    max_depth: usize,
+    // How far the ambient occlusion integrator looks for occluders.
+    ao_distance: f64,
+    sampler: String,
+    filter: String,
+    // None picks the filter's usual radius.
+    filter_radius: Option<f64>,
+    threads: usize,
+    seed: u64,
+    // Density of the fog filling the random scene; None leaves the air clear.
+    haze: Option<f64>,
+    // None prints a P3 image to stdout.
+    output: Option<String>,
+    env: Option<String>,
+    env_rotation: f64,
+    env_intensity: f64,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        scene: "random".to_string(),
+        integrator: "path".to_string(),
+        min_bounces: 3,
+        max_depth: 50,
+        ao_distance: 1.0,
+        sampler: "sobol".to_string(),
+        filter: "box".to_string(),
+        filter_radius: None,
+        threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        seed: 0,
+        haze: None,
+        output: None,
+        env: None,
+        env_rotation: 0.0,
+        env_intensity: 1.0,
+    };
+
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            options.scene = arg;
+            continue;
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {}", arg))?;
+        let number = || {
+            value
+                .parse::<f64>()
+                .map_err(|_| format!("invalid number '{}' for {}", value, arg))
+        };
+        match arg.as_str() {
+            "--integrator" => options.integrator = value.clone(),
+            "--min-bounces" => {
+                options.min_bounces = value
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid bounce count '{}'", value))?
+            }
+            "--max-depth" => {
+                options.max_depth = value
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid depth '{}'", value))?
+            }
+            "--ao-distance" => {
+                options.ao_distance = number()
+                    .ok()
+                    .filter(|d| d.is_finite() && *d > 0.0)
+                    .ok_or_else(|| format!("invalid occlusion distance '{}'", value))?
+            }
+            "--sampler" => options.sampler = value.clone(),
+            "--filter" => options.filter = value.clone(),
+            "--filter-radius" => {
+                options.filter_radius = Some(
+                    number()
+                        .ok()
+                        .filter(|r| r.is_finite() && *r > 0.0)
+                        .ok_or_else(|| format!("invalid filter radius '{}'", value))?,
+                )
+            }
+            "--threads" => {
+                options.threads = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| format!("invalid thread count '{}'", value))?
+            }
+            "--seed" => {
+                options.seed = value
+                    .parse::<u64>()
+                    .map_err(|_| format!("invalid seed '{}'", value))?
+            }
+            "--haze" => {
+                options.haze = Some(
+                    number()
+                        .ok()
+                        .filter(|d| d.is_finite() && *d >= 0.0)
+                        .ok_or_else(|| format!("invalid haze density '{}'", value))?,
+                )
+            }
+            "--output" => {
+                // Reject an unwritable format before spending time rendering.
+                if ImageFormat::from_path(Path::new(&value)).is_none() {
+                    return Err(format!("unrecognized output format '{}'", value));
+                }
+                options.output = Some(value.clone())
+            }
+            "--env" => options.env = Some(value.clone()),
+            "--env-rotation" => options.env_rotation = number()?,
+            "--env-intensity" => options.env_intensity = number()?,
+            _ => return Err(format!("unknown option {}", arg)),
+        }
+    }
+
+    Ok(options)
+}
 
 fn main() {
     // Image
@@ -31,17 +173,87 @@ fn main() {
     const IMAGE_WIDTH: usize = 1200;
     const IMAGE_HEIGHT: usize = (IMAGE_WIDTH as f64 / ASPECT_RATIO) as usize;
     const SAMPLES_PER_PIXEL: usize = 500;
-    const MAX_DEPTH: i32 = 50;
+
+    let options = parse_args(std::env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}\n{}", e, USAGE);
+        process::exit(2);
+    });
 
     // World
-    let world = random_scene();
+    let (mut scene, lookfrom, lookat, aperture) = match options.scene.as_str() {
+        "random" => (
+            random_scene(options.seed, options.haze),
+            Point3::new(13.0, 2.0, 3.0),
+            Point3::new(0.0, 0.0, 0.0),
+            0.1,
+        ),
+        "simple_light" => (
+            simple_light(),
+            Point3::new(26.0, 3.0, 6.0),
+            Point3::new(0.0, 2.0, 0.0),
+            0.0,
+        ),
+        other => {
+            eprintln!("unknown scene '{}'\n{}", other, USAGE);
+            process::exit(2);
+        }
+    };
+
+    // An environment map, when given, replaces the scene's own background.
+    if let Some(env_path) = &options.env {
+        match EnvironmentMap::load(
+            Path::new(env_path),
+            options.env_rotation,
+            options.env_intensity,
+        ) {
+            Ok(env) => scene.background = Arc::new(env),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+    }
+
+    // Integrator
+    let integrator: Box<dyn Integrator> = match options.integrator.as_str() {
+        "path" => Box::new(PathTracer::new(options.min_bounces)),
+        "whitted" => Box::new(WhittedTracer::new(options.max_depth)),
+        "ao" => Box::new(AmbientOcclusion::new(1, options.ao_distance)),
+        other => {
+            eprintln!("unknown integrator '{}'\n{}", other, USAGE);
+            process::exit(2);
+        }
+    };
+
+    // Sampler
+    let sampler: Box<dyn Sampler> = match options.sampler.as_str() {
+        "independent" => Box::new(IndependentSampler::new(options.seed)),
+        "stratified" => Box::new(StratifiedSampler::new(SAMPLES_PER_PIXEL, options.seed)),
+        "halton" => Box::new(HaltonSampler::new(options.seed)),
+        "sobol" => Box::new(SobolSampler::new(options.seed)),
+        other => {
+            eprintln!("unknown sampler '{}'\n{}", other, USAGE);
+            process::exit(2);
+        }
+    };
+
+    // Reconstruction filter
+    let radius = |default: f64| options.filter_radius.unwrap_or(default);
+    let filter: Box<dyn Filter> = match options.filter.as_str() {
+        "box" => Box::new(BoxFilter::new(radius(0.5))),
+        "tent" => Box::new(TentFilter::new(radius(1.0))),
+        "gaussian" => Box::new(GaussianFilter::new(radius(1.5))),
+        "mitchell" => Box::new(MitchellFilter::new(radius(2.0))),
+        "lanczos" => Box::new(LanczosFilter::new(radius(3.0))),
+        other => {
+            eprintln!("unknown filter '{}'\n{}", other, USAGE);
+            process::exit(2);
+        }
+    };
 
     // Camera
-    let lookfrom = Point3::new(13.0, 2.0, 3.0);
-    let lookat = Point3::new(0.0, 0.0, 0.0);
     let vup = Vec3::new(0.0, 1.0, 0.0);
     let dist_to_focus = 10.0;
-    let aperture = 0.1;
     let camera = Camera::new(
         &lookfrom,
         &lookat,
@@ -50,55 +262,53 @@ fn main() {
         ASPECT_RATIO,
         aperture,
         dist_to_focus,
+        0.0,
+        1.0,
     );
 
     // Render
-    print!("P3\n{} {}\n255\n", IMAGE_WIDTH, IMAGE_HEIGHT);
-
-    for j in (0..IMAGE_HEIGHT).rev() {
-        eprint!("\rScanlines remaining: {} ", j);
-        io::stderr().flush().unwrap();
-        for i in 0..IMAGE_WIDTH {
-            let mut pixel_color = Vec3::new(0.0, 0.0, 0.0);
-            for s in 0..SAMPLES_PER_PIXEL {
-                let u = (i as f64 + random_double()) / (IMAGE_WIDTH as f64 - 1.0);
-                let v = (j as f64 + random_double()) / (IMAGE_HEIGHT as f64 - 1.0);
-                let r = camera.get_ray(u, v);
-                pixel_color += ray_color(&r, &world, MAX_DEPTH);
-            }
-
-            let s = pixel_color.as_color_repr(SAMPLES_PER_PIXEL);
-            print!("{}", s);
-        }
-    }
+    let settings = RenderSettings {
+        width: IMAGE_WIDTH,
+        height: IMAGE_HEIGHT,
+        samples_per_pixel: SAMPLES_PER_PIXEL,
+        tile_size: 16,
+        threads: options.threads,
+    };
+    let film = render(
+        &camera,
+        &scene,
+        integrator.as_ref(),
+        sampler.as_ref(),
+        filter.as_ref(),
+        &settings,
+        &mut |done, total| {
+            eprint!("\rTiles remaining: {} ", total - done);
+            // Progress is best effort; a closed stderr shouldn't stop the render.
+            let _ = io::stderr().flush();
+        },
+    );
     eprintln!("\nDone.");
-}
-
-fn ray_color(r: &Ray, world: &dyn Hittable, depth: i32) -> Vec3 {
-    let mut rec = HitRecord::default();
 
-    // If we've exceeded the ray bounce limit, no more light is gathered.
-    if depth <= 0 {
-        return Vec3::new(0.0, 0.0, 0.0);
-    }
-
-    if world.hit(r, 0.001, INFINITY, &mut rec) {
-        let mut scattered = Ray::new(&Vec3::new(0.0, 0.0, 0.0), &Vec3::new(0.0, 0.0, 0.0));
-        let mut attenuation = Vec3::new(0.0, 0.0, 0.0);
-        if rec
-            .material
-            .scatter(r, &rec, &mut attenuation, &mut scattered)
-        {
-            return attenuation * ray_color(&scattered, world, depth - 1);
+    match &options.output {
+        Some(path) => {
+            if let Err(e) = save_image(&film, Path::new(path)) {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+        None => {
+            let mut out = BufWriter::new(io::stdout().lock());
+            if let Err(e) = write_image(&film, ImageFormat::Ppm, &mut out).and_then(|_| out.flush())
+            {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
         }
     }
-
-    let unit_direction = r.dir().unit_vector();
-    let t = 0.5 * (unit_direction.y() + 1.0);
-    return (1.0 - t) * Vec3::new(1.0, 1.0, 1.0) + t * Vec3::new(0.5, 0.7, 1.0);
 }
 
-pub fn random_scene() -> HittableList {
+pub fn random_scene(seed: u64, haze: Option<f64>) -> Scene {
+    let mut sampler = IndependentSampler::new(seed);
     let mut world = HittableList::new();
 
     let ground_material = Arc::new(Lambertian::new(&Vec3::new(0.5, 0.5, 0.5)));
@@ -110,11 +320,11 @@ pub fn random_scene() -> HittableList {
 
     for a in -11..11 {
         for b in -11..11 {
-            let choose_mat = random_double();
+            let choose_mat = sampler.get_1d();
             let center = Point3::new(
-                a as f64 + 0.9 * random_double(),
+                a as f64 + 0.9 * sampler.get_1d(),
                 0.2,
-                b as f64 + 0.9 * random_double(),
+                b as f64 + 0.9 * sampler.get_1d(),
             );
 
             if (center - Point3::new(4.0, 0.2, 0.0)).length() > 0.9 {
@@ -122,13 +332,21 @@ pub fn random_scene() -> HittableList {
 
                 if choose_mat < 0.8 {
                     // diffuse
-                    let albedo = Vec3::random() * Vec3::random();
+                    let albedo = Vec3::random(&mut sampler) * Vec3::random(&mut sampler);
                     sphere_material = Arc::new(Lambertian::new(&albedo));
-                    world.add(Arc::new(Sphere::new(center, 0.2, sphere_material)));
+                    let center2 = center + Vec3::new(0.0, sampler.get_range(0.0, 0.5), 0.0);
+                    world.add(Arc::new(MovingSphere::new(
+                        center,
+                        center2,
+                        0.0,
+                        1.0,
+                        0.2,
+                        sphere_material,
+                    )));
                 } else if choose_mat < 0.95 {
                     // metal
-                    let albedo = Vec3::random_by_range(0.5, 1.0);
-                    let fuzz = random_double_by_range(0.0, 0.5);
+                    let albedo = Vec3::random_by_range(0.5, 1.0, &mut sampler);
+                    let fuzz = sampler.get_range(0.0, 0.5);
                     sphere_material = Arc::new(Metal::new(&albedo, fuzz));
                     world.add(Arc::new(Sphere::new(center, 0.2, sphere_material)));
                 } else {
@@ -161,5 +379,66 @@ pub fn random_scene() -> HittableList {
         material3,
     )));
 
-    world
+    // Haze over the whole scene; 0.002 makes a thin one.
+    if let Some(density) = haze {
+        let boundary = Arc::new(Sphere::new(
+            Point3::new(0.0, 0.0, 0.0),
+            50.0,
+            Arc::new(Dielectric::new(1.5)),
+        ));
+        world.add(Arc::new(ConstantMedium::new(
+            boundary,
+            density,
+            &Vec3::new(1.0, 1.0, 1.0),
+        )));
+    }
+
+    Scene::new(
+        Arc::new(BvhNode::new(&world)),
+        Arc::new(GradientBackground::sky()),
+    )
+}
+
+// Two marble spheres lit only by a rectangular and a spherical light.
+pub fn simple_light() -> Scene {
+    let mut world = HittableList::new();
+
+    let marble = Arc::new(Lambertian::with_texture(Arc::new(MarbleTexture::new(
+        0,
+        4.0,
+        &Vec3::new(1.0, 1.0, 1.0),
+    ))));
+    world.add(Arc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        marble.clone(),
+    )));
+    world.add(Arc::new(Sphere::new(
+        Point3::new(0.0, 2.0, 0.0),
+        2.0,
+        marble,
+    )));
+
+    let light = Arc::new(DiffuseLight::new(&Vec3::new(4.0, 4.0, 4.0)));
+    let mut lights = HittableList::new();
+    lights.add(Arc::new(Quad::new(
+        Point3::new(3.0, 1.0, -2.0),
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(0.0, 2.0, 0.0),
+        light.clone(),
+    )));
+    lights.add(Arc::new(Sphere::new(
+        Point3::new(0.0, 7.0, 0.0),
+        2.0,
+        light,
+    )));
+    for light in lights.objects.iter() {
+        world.add(light.clone());
+    }
+
+    Scene::new(
+        Arc::new(BvhNode::new(&world)),
+        Arc::new(SolidBackground::black()),
+    )
+    .with_lights(lights)
 }
@@ -1,9 +1,9 @@
 use crate::{
     model::{hit::HitRecord, ray::Ray, vec3::Vec3},
-    util::rtweekend::random_double,
+    sampler::Sampler,
 };
 
-use super::material::Material;
+use super::material::{Material, ScatterRecord};
 
 pub struct Dielectric {
     pub ir: f64,
@@ -25,14 +25,17 @@ impl Dielectric {
 }
 
 impl Material for Dielectric {
-    fn scatter(
+    // Both the reflected and the refracted lobe are deltas.
+    fn sample(
         &self,
         r_in: &Ray,
         rec: &HitRecord,
-        attenuation: &mut Vec3,
-        scattered: &mut Ray,
+        srec: &mut ScatterRecord,
+        sampler: &mut dyn Sampler,
     ) -> bool {
-        *attenuation = Vec3::new(1.0, 1.0, 1.0);
+        srec.attenuation = Vec3::new(1.0, 1.0, 1.0);
+        srec.pdf = 0.0;
+        srec.is_specular = true;
         let refraction_ratio = if rec.front_face {
             1.0 / self.ir
         } else {
@@ -44,15 +47,15 @@ impl Material for Dielectric {
         let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
 
         let cannot_refract = refraction_ratio * sin_theta > 1.0;
-        let direction: Vec3;
-        if cannot_refract || Dielectric::reflectance(cos_theta, refraction_ratio) > random_double()
+        let direction = if cannot_refract
+            || Dielectric::reflectance(cos_theta, refraction_ratio) > sampler.get_1d()
         {
-            direction = unit_direction.reflect(&rec.normal);
+            unit_direction.reflect(&rec.normal)
         } else {
-            direction = unit_direction.refract(&rec.normal, refraction_ratio);
-        }
+            unit_direction.refract(&rec.normal, refraction_ratio)
+        };
 
-        *scattered = Ray::new(&rec.p, &direction);
-        return true;
+        srec.scattered = Ray::new(&rec.p, &direction, r_in.time());
+        true
     }
 }
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use crate::{
+    model::{hit::HitRecord, ray::Ray, vec3::Vec3},
+    sampler::Sampler,
+    texture::{solid_color::SolidColor, texture::Texture},
+};
+
+use super::material::{Material, ScatterRecord};
+
+use Vec3 as Point3;
+
+// An emitter: radiates its texture's color and reflects nothing.
+pub struct DiffuseLight {
+    pub emit: Arc<dyn Texture>,
+}
+
+impl DiffuseLight {
+    pub fn new(color: &Vec3) -> Self {
+        DiffuseLight::with_texture(Arc::new(SolidColor::new(color)))
+    }
+
+    pub fn with_texture(emit: Arc<dyn Texture>) -> Self {
+        Self { emit }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn sample(
+        &self,
+        _r_in: &Ray,
+        _rec: &HitRecord,
+        _srec: &mut ScatterRecord,
+        _sampler: &mut dyn Sampler,
+    ) -> bool {
+        false
+    }
+
+    fn emitted(&self, u: f64, v: f64, p: &Point3) -> Vec3 {
+        self.emit.value(u, v, p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::lambertian::Lambertian, sampler::independent::IndependentSampler};
+
+    #[test]
+    fn test_diffuse_light_emits_without_scattering() {
+        let light = DiffuseLight::new(&Vec3::new(4.0, 2.0, 1.0));
+        let p = Point3::default();
+        assert_eq!(Vec3::new(4.0, 2.0, 1.0), light.emitted(0.5, 0.5, &p));
+
+        let r = Ray::new(&p, &Vec3::new(0.0, 0.0, -1.0), 0.0);
+        let mut srec = ScatterRecord::default();
+        assert!(!light.sample(
+            &r,
+            &HitRecord::default(),
+            &mut srec,
+            &mut IndependentSampler::new(0)
+        ));
+
+        // Non-emitters fall back to the trait's black.
+        let diffuse = Lambertian::new(&Vec3::new(0.5, 0.5, 0.5));
+        assert_eq!(Vec3::default(), diffuse.emitted(0.5, 0.5, &p));
+    }
+}
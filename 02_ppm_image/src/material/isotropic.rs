@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use crate::{
+    model::{hit::HitRecord, ray::Ray, vec3::Vec3},
+    sampler::Sampler,
+    texture::{solid_color::SolidColor, texture::Texture},
+    util::rtweekend::PI,
+};
+
+use super::material::{Material, ScatterRecord};
+
+// Phase function for participating media: scatters uniformly in all directions.
+pub struct Isotropic {
+    pub albedo: Arc<dyn Texture>,
+}
+
+impl Isotropic {
+    pub fn new(albedo: &Vec3) -> Self {
+        Isotropic::with_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn with_texture(albedo: Arc<dyn Texture>) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Material for Isotropic {
+    fn sample(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        srec: &mut ScatterRecord,
+        sampler: &mut dyn Sampler,
+    ) -> bool {
+        srec.scattered = Ray::new(&rec.p, &Vec3::random_unit_vector(sampler), r_in.time());
+        srec.attenuation = self.albedo.value(rec.u, rec.v, &rec.p);
+        srec.pdf = 1.0 / (4.0 * PI);
+        srec.is_specular = false;
+        true
+    }
+
+    fn eval(&self, _r_in: &Ray, rec: &HitRecord, _scattered: &Ray) -> Vec3 {
+        self.albedo.value(rec.u, rec.v, &rec.p) / (4.0 * PI)
+    }
+
+    fn pdf(&self, _r_in: &Ray, _rec: &HitRecord, _scattered: &Ray) -> f64 {
+        1.0 / (4.0 * PI)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sampler::independent::IndependentSampler;
+
+    #[test]
+    fn test_isotropic_scatters_uniformly() {
+        let isotropic = Isotropic::new(&Vec3::new(0.8, 0.6, 0.4));
+        let rec = HitRecord::default();
+        let r_in = Ray::new(&Vec3::new(0.0, 0.0, -1.0), &Vec3::new(0.0, 0.0, 1.0), 0.5);
+        let mut srec = ScatterRecord::default();
+        let mut sampler = IndependentSampler::new(0);
+
+        // Every direction is equally likely, so the scattered directions
+        // average out to nothing.
+        let n = 10000;
+        let mut sum = Vec3::default();
+        for _ in 0..n {
+            assert!(isotropic.sample(&r_in, &rec, &mut srec, &mut sampler));
+            assert!(!srec.is_specular);
+            assert!((srec.scattered.dir().length() - 1.0).abs() < 1e-12);
+            assert_eq!(0.5, srec.scattered.time());
+            assert_eq!(srec.pdf, isotropic.pdf(&r_in, &rec, &srec.scattered));
+            sum += *srec.scattered.dir();
+        }
+        assert!((sum / n as f64).length() < 0.05);
+
+        let f = isotropic.eval(&r_in, &rec, &srec.scattered);
+        assert!((f * 4.0 * PI - Vec3::new(0.8, 0.6, 0.4)).near_zero());
+    }
+}
@@ -1,36 +1,52 @@
-use crate::model::{hit::HitRecord, ray::Ray, vec3::Vec3};
+use std::sync::Arc;
 
-use super::material::Material;
+use crate::{
+    model::{hit::HitRecord, onb::Onb, ray::Ray, vec3::Vec3},
+    sampler::Sampler,
+    texture::{solid_color::SolidColor, texture::Texture},
+    util::rtweekend::PI,
+};
+
+use super::material::{Material, ScatterRecord};
 
 pub struct Lambertian {
-    pub albedo: Vec3,
+    pub albedo: Arc<dyn Texture>,
 }
 
 impl Lambertian {
     pub fn new(albedo: &Vec3) -> Lambertian {
-        Self {
-            albedo: albedo.clone(),
-        }
+        Lambertian::with_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn with_texture(albedo: Arc<dyn Texture>) -> Lambertian {
+        Self { albedo }
     }
 }
 
 impl Material for Lambertian {
-    fn scatter(
+    fn sample(
         &self,
         r_in: &Ray,
         rec: &HitRecord,
-        attenuation: &mut Vec3,
-        scattered: &mut Ray,
+        srec: &mut ScatterRecord,
+        sampler: &mut dyn Sampler,
     ) -> bool {
-        let mut scatter_direction = rec.normal + Vec3::random_unit_vector();
+        let uvw = Onb::build_from_w(&rec.normal);
+        let scatter_direction = uvw.local(&Vec3::random_cosine_direction(sampler));
 
-        // Catch degenerate scatter direction
-        if scatter_direction.near_zero() {
-            scatter_direction = rec.normal;
-        }
+        srec.scattered = Ray::new(&rec.p, &scatter_direction, r_in.time());
+        srec.attenuation = self.albedo.value(rec.u, rec.v, &rec.p);
+        srec.pdf = self.pdf(r_in, rec, &srec.scattered);
+        srec.is_specular = false;
+        true
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Vec3 {
+        self.albedo.value(rec.u, rec.v, &rec.p) * self.pdf(r_in, rec, scattered)
+    }
 
-        *scattered = Ray::new(&rec.p, &scatter_direction);
-        *attenuation = self.albedo.clone();
-        return true;
+    fn pdf(&self, _r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        let cosine = rec.normal.dot(&scattered.dir().unit_vector());
+        cosine.max(0.0) / PI
     }
 }
@@ -1,11 +1,60 @@
-use crate::model::{hit::HitRecord, ray::Ray, vec3::Vec3};
+use crate::{
+    model::{hit::HitRecord, ray::Ray, vec3::Vec3},
+    sampler::Sampler,
+};
 
-pub trait Material {
-    fn scatter(
+use Vec3 as Point3;
+
+// One direction drawn from a material's scattering distribution.
+pub struct ScatterRecord {
+    pub scattered: Ray,
+    // What the path's throughput is multiplied by: eval / pdf for the
+    // scattered direction, or just the tint of a specular lobe.
+    pub attenuation: Vec3,
+    // Solid-angle density of `scattered`; unused for specular lobes.
+    pub pdf: f64,
+    // A perfect mirror or refraction: a single possible direction (a delta
+    // lobe) that `eval` and `pdf` report as zero and light sampling can
+    // never find.
+    pub is_specular: bool,
+}
+
+impl Default for ScatterRecord {
+    fn default() -> Self {
+        Self {
+            scattered: Ray::new(&Point3::default(), &Vec3::default(), 0.0),
+            attenuation: Vec3::default(),
+            pdf: 0.0,
+            is_specular: false,
+        }
+    }
+}
+
+pub trait Material: Send + Sync {
+    // Picks a scattered direction for r_in at the hit. Returns false only for
+    // materials that never scatter (pure emitters or absorbers); a sample
+    // that happens to carry nothing has zero attenuation instead.
+    fn sample(
         &self,
         r_in: &Ray,
         rec: &HitRecord,
-        attenuation: &mut Vec3,
-        scattered: &mut Ray,
+        srec: &mut ScatterRecord,
+        sampler: &mut dyn Sampler,
     ) -> bool;
+
+    // The BSDF times |cos θ| for light arriving along `scattered` and leaving
+    // back along r_in. Delta lobes are not included.
+    fn eval(&self, _r_in: &Ray, _rec: &HitRecord, _scattered: &Ray) -> Vec3 {
+        Vec3::new(0.0, 0.0, 0.0)
+    }
+
+    // Density with which `sample` picks `scattered`, excluding delta lobes.
+    fn pdf(&self, _r_in: &Ray, _rec: &HitRecord, _scattered: &Ray) -> f64 {
+        0.0
+    }
+
+    // Light given off at the hit point; only emitters override this.
+    fn emitted(&self, _u: f64, _v: f64, _p: &Point3) -> Vec3 {
+        Vec3::new(0.0, 0.0, 0.0)
+    }
 }
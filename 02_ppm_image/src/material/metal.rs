@@ -1,35 +1,166 @@
-use crate::model::{hit::HitRecord, ray::Ray, vec3::Vec3};
+use std::sync::Arc;
 
-use super::material::Material;
+use crate::{
+    model::{hit::HitRecord, onb::Onb, ray::Ray, vec3::Vec3},
+    sampler::Sampler,
+    texture::{solid_color::SolidColor, texture::Texture},
+    util::rtweekend::PI,
+};
 
+use super::material::{Material, ScatterRecord};
+
+// A mirror when fuzz is 0, otherwise a glossy reflector whose lobe about the
+// mirror direction widens with fuzz: a normalized Phong lobe with exponent
+// 2/fuzz² - 2, so fuzz 1 spreads over the whole hemisphere.
 pub struct Metal {
-    pub albedo: Vec3,
+    pub albedo: Arc<dyn Texture>,
     pub fuzz: f64,
 }
 
 impl Metal {
     pub fn new(albedo: &Vec3, f: f64) -> Self {
+        Metal::with_texture(Arc::new(SolidColor::new(albedo)), f)
+    }
+
+    pub fn with_texture(albedo: Arc<dyn Texture>, f: f64) -> Self {
         Self {
-            albedo: albedo.clone(),
+            albedo,
             fuzz: if f < 1.0 { f } else { 1.0 },
         }
     }
+
+    fn exponent(&self) -> f64 {
+        2.0 / (self.fuzz * self.fuzz) - 2.0
+    }
 }
 
 impl Material for Metal {
-    fn scatter(
+    fn sample(
         &self,
         r_in: &Ray,
         rec: &HitRecord,
-        attenuation: &mut Vec3,
-        scattered: &mut Ray,
+        srec: &mut ScatterRecord,
+        sampler: &mut dyn Sampler,
     ) -> bool {
         let reflected = r_in.dir().unit_vector().reflect(&rec.normal);
-        *scattered = Ray::new(
-            &rec.p,
-            &(reflected + self.fuzz * Vec3::random_in_unit_sphere()),
+        srec.attenuation = self.albedo.value(rec.u, rec.v, &rec.p);
+
+        if self.fuzz <= 0.0 {
+            srec.scattered = Ray::new(&rec.p, &reflected, r_in.time());
+            srec.pdf = 0.0;
+            srec.is_specular = true;
+            return true;
+        }
+
+        // cos α = u^(1 / (n + 1)) picks the angle from the mirror direction.
+        let (u0, u1) = sampler.get_2d();
+        let cos_alpha = u0.powf(1.0 / (self.exponent() + 1.0));
+        let sin_alpha = (1.0 - cos_alpha * cos_alpha).max(0.0).sqrt();
+        let phi = 2.0 * PI * u1;
+        let local = Vec3::new(phi.cos() * sin_alpha, phi.sin() * sin_alpha, cos_alpha);
+        let direction = Onb::build_from_w(&reflected).local(&local);
+
+        srec.scattered = Ray::new(&rec.p, &direction, r_in.time());
+        srec.pdf = self.pdf(r_in, rec, &srec.scattered);
+        srec.is_specular = false;
+        // A direction below the surface carries nothing, but the hit still
+        // reflects light found by sampling the lights, so it isn't absorbed.
+        if direction.dot(&rec.normal) <= 0.0 {
+            srec.attenuation = Vec3::new(0.0, 0.0, 0.0);
+        }
+        true
+    }
+
+    // Taken as albedo × lobe density, so a sampled direction's weight is
+    // exactly the albedo, as it was for the fuzzed mirror.
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Vec3 {
+        self.albedo.value(rec.u, rec.v, &rec.p) * self.pdf(r_in, rec, scattered)
+    }
+
+    fn pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        if self.fuzz <= 0.0 {
+            return 0.0;
+        }
+
+        let dir = scattered.dir().unit_vector();
+        if dir.dot(&rec.normal) <= 0.0 {
+            return 0.0;
+        }
+        let reflected = r_in.dir().unit_vector().reflect(&rec.normal);
+        let cos_alpha = reflected.dot(&dir);
+        if cos_alpha <= 0.0 {
+            return 0.0;
+        }
+        let n = self.exponent();
+
+        (n + 1.0) / (2.0 * PI) * cos_alpha.powf(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sampler::independent::IndependentSampler;
+
+    fn hit_at_origin() -> HitRecord {
+        HitRecord {
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            front_face: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_mirror_is_specular() {
+        let metal = Metal::new(&Vec3::new(0.9, 0.9, 0.9), 0.0);
+        let rec = hit_at_origin();
+        let r_in = Ray::new(&Vec3::new(-1.0, 1.0, 0.0), &Vec3::new(1.0, -1.0, 0.0), 0.0);
+        let mut srec = ScatterRecord::default();
+        let mut sampler = IndependentSampler::new(0);
+
+        assert!(metal.sample(&r_in, &rec, &mut srec, &mut sampler));
+        assert!(srec.is_specular);
+        assert!(
+            (srec.scattered.dir().unit_vector() - Vec3::new(1.0, 1.0, 0.0).unit_vector())
+                .near_zero()
         );
-        *attenuation = self.albedo.clone();
-        scattered.dir().dot(&rec.normal) > 0.0
+        assert_eq!(0.0, metal.pdf(&r_in, &rec, &srec.scattered));
+    }
+
+    #[test]
+    fn test_glossy_pdf_integrates_to_at_most_one() {
+        let metal = Metal::new(&Vec3::new(0.9, 0.9, 0.9), 0.3);
+        let rec = hit_at_origin();
+        let r_in = Ray::new(&Vec3::new(0.0, 1.0, 0.0), &Vec3::new(0.0, -1.0, 0.0), 0.0);
+
+        // The lobe is centered on the normal, so it lies entirely above the
+        // surface and integrates to one.
+        let n = 400;
+        let mut total = 0.0;
+        for i in 0..n {
+            for j in 0..n {
+                let theta = 0.5 * PI * (i as f64 + 0.5) / n as f64;
+                let phi = 2.0 * PI * (j as f64 + 0.5) / n as f64;
+                let dir = Vec3::new(
+                    theta.sin() * phi.cos(),
+                    theta.cos(),
+                    theta.sin() * phi.sin(),
+                );
+                let scattered = Ray::new(&rec.p, &dir, 0.0);
+                total += metal.pdf(&r_in, &rec, &scattered)
+                    * theta.sin()
+                    * (0.5 * PI / n as f64)
+                    * (2.0 * PI / n as f64);
+            }
+        }
+        assert!((total - 1.0).abs() < 1e-2);
+
+        let mut srec = ScatterRecord::default();
+        let mut sampler = IndependentSampler::new(0);
+        for _ in 0..50 {
+            assert!(metal.sample(&r_in, &rec, &mut srec, &mut sampler));
+            assert!(!srec.is_specular);
+            assert!((srec.pdf - metal.pdf(&r_in, &rec, &srec.scattered)).abs() < 1e-9);
+        }
     }
 }
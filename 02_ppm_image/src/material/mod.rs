@@ -1,4 +1,7 @@
 pub mod dielectric;
+pub mod diffuse_light;
+pub mod isotropic;
 pub mod lambertian;
+#[allow(clippy::module_inception)]
 pub mod material;
 pub mod metal;
@@ -0,0 +1,173 @@
+use super::{ray::Ray, vec3::Vec3};
+
+use Vec3 as Point3;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Aabb {
+    minimum: Point3,
+    maximum: Point3,
+}
+
+impl Aabb {
+    pub fn new(a: &Point3, b: &Point3) -> Self {
+        // Accept the two corners in any order.
+        Self {
+            minimum: Point3::new(a.x().min(b.x()), a.y().min(b.y()), a.z().min(b.z())),
+            maximum: Point3::new(a.x().max(b.x()), a.y().max(b.y()), a.z().max(b.z())),
+        }
+    }
+
+    pub fn min(&self) -> &Point3 {
+        &self.minimum
+    }
+
+    pub fn max(&self) -> &Point3 {
+        &self.maximum
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        0.5 * (self.minimum + self.maximum)
+    }
+
+    pub fn longest_axis(&self) -> usize {
+        let extent = self.maximum - self.minimum;
+        if extent.x() > extent.y() && extent.x() > extent.z() {
+            0
+        } else if extent.y() > extent.z() {
+            1
+        } else {
+            2
+        }
+    }
+
+    // Grow any side thinner than delta so flat primitives (quads, triangles
+    // lying in an axis plane) still have a box a ray can enter.
+    pub fn pad(&self, delta: f64) -> Aabb {
+        let mut minimum = [0.0; 3];
+        let mut maximum = [0.0; 3];
+        for a in 0..3 {
+            if self.maximum[a] - self.minimum[a] < delta {
+                minimum[a] = self.minimum[a] - delta / 2.0;
+                maximum[a] = self.maximum[a] + delta / 2.0;
+            } else {
+                minimum[a] = self.minimum[a];
+                maximum[a] = self.maximum[a];
+            }
+        }
+
+        Aabb {
+            minimum: Point3::new(minimum[0], minimum[1], minimum[2]),
+            maximum: Point3::new(maximum[0], maximum[1], maximum[2]),
+        }
+    }
+
+    // Slab test: clip [t_min, t_max] against the pair of planes on each axis.
+    // Rays parallel to an axis never cross its slab, so they either lie inside
+    // it for the whole interval or miss the box outright; handling that case
+    // up front avoids the 0 * inf NaN the division would produce.
+    pub fn hit(&self, r: &Ray, mut t_min: f64, mut t_max: f64) -> bool {
+        for a in 0..3 {
+            let origin = r.origin()[a];
+            if r.dir()[a] == 0.0 {
+                if origin < self.minimum[a] || origin > self.maximum[a] {
+                    return false;
+                }
+                continue;
+            }
+
+            let inv_d = 1.0 / r.dir()[a];
+            let t0 = (self.minimum[a] - origin) * inv_d;
+            let t1 = (self.maximum[a] - origin) * inv_d;
+
+            t_min = t_min.max(t0.min(t1));
+            t_max = t_max.min(t0.max(t1));
+            if t_max <= t_min {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    pub fn surrounding_box(box0: &Aabb, box1: &Aabb) -> Aabb {
+        let small = Point3::new(
+            box0.min().x().min(box1.min().x()),
+            box0.min().y().min(box1.min().y()),
+            box0.min().z().min(box1.min().z()),
+        );
+        let big = Point3::new(
+            box0.max().x().max(box1.max().x()),
+            box0.max().y().max(box1.max().y()),
+            box0.max().z().max(box1.max().z()),
+        );
+
+        Aabb {
+            minimum: small,
+            maximum: big,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_box() -> Aabb {
+        Aabb::new(&Point3::new(-1.0, -1.0, -1.0), &Point3::new(1.0, 1.0, 1.0))
+    }
+
+    #[test]
+    fn test_aabb_new_orders_corners() {
+        let b = Aabb::new(&Point3::new(1.0, -2.0, 3.0), &Point3::new(-1.0, 2.0, -3.0));
+        assert_eq!(Point3::new(-1.0, -2.0, -3.0), *b.min());
+        assert_eq!(Point3::new(1.0, 2.0, 3.0), *b.max());
+    }
+
+    #[test]
+    fn test_aabb_hit() {
+        let r = Ray::new(&Point3::new(0.0, 0.0, -5.0), &Vec3::new(0.0, 0.0, 1.0), 0.0);
+        assert!(unit_box().hit(&r, 0.0, f64::INFINITY));
+
+        let r = Ray::new(
+            &Point3::new(0.0, 0.0, -5.0),
+            &Vec3::new(0.0, 0.0, -1.0),
+            0.0,
+        );
+        assert!(!unit_box().hit(&r, 0.0, f64::INFINITY));
+
+        let r = Ray::new(&Point3::new(0.0, 2.0, -5.0), &Vec3::new(0.0, 0.0, 1.0), 0.0);
+        assert!(!unit_box().hit(&r, 0.0, f64::INFINITY));
+    }
+
+    #[test]
+    fn test_aabb_hit_respects_interval() {
+        let r = Ray::new(&Point3::new(0.0, 0.0, -5.0), &Vec3::new(0.0, 0.0, 1.0), 0.0);
+        assert!(!unit_box().hit(&r, 0.0, 3.0));
+        assert!(unit_box().hit(&r, 0.0, 4.5));
+    }
+
+    #[test]
+    fn test_aabb_hit_ray_in_slab_plane() {
+        let r = Ray::new(&Point3::new(1.0, 0.0, -5.0), &Vec3::new(0.0, 0.0, 1.0), 0.0);
+        assert!(unit_box().hit(&r, 0.0, f64::INFINITY));
+    }
+
+    #[test]
+    fn test_aabb_pad() {
+        let flat = Aabb::new(&Point3::new(0.0, 0.0, 0.0), &Point3::new(1.0, 1.0, 0.0));
+        let padded = flat.pad(0.0002);
+        assert_eq!(0.0, padded.min().x());
+        assert_eq!(-0.0001, padded.min().z());
+        assert_eq!(0.0001, padded.max().z());
+    }
+
+    #[test]
+    fn test_aabb_surrounding_box() {
+        let a = Aabb::new(&Point3::new(0.0, 0.0, 0.0), &Point3::new(1.0, 1.0, 1.0));
+        let b = Aabb::new(&Point3::new(-1.0, 0.5, 0.5), &Point3::new(0.5, 3.0, 0.75));
+        let s = Aabb::surrounding_box(&a, &b);
+        assert_eq!(Point3::new(-1.0, 0.0, 0.0), *s.min());
+        assert_eq!(Point3::new(1.0, 3.0, 1.0), *s.max());
+        assert_eq!(1, s.longest_axis());
+    }
+}
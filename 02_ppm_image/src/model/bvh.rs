@@ -0,0 +1,218 @@
+use std::{cmp::Ordering, sync::Arc};
+
+use crate::sampler::Sampler;
+
+use super::{
+    aabb::Aabb,
+    hit::{HitRecord, Hittable, HittableList},
+    ray::Ray,
+};
+
+pub struct BvhNode {
+    left: Arc<dyn Hittable>,
+    right: Arc<dyn Hittable>,
+    // None when the node holds objects without a bounding box, which every
+    // ray has to be tested against.
+    bbox: Option<Aabb>,
+}
+
+impl BvhNode {
+    pub fn new(list: &HittableList) -> Self {
+        let mut objects = list.objects.clone();
+        BvhNode::from_objects(&mut objects)
+    }
+
+    pub fn from_objects(objects: &mut [Arc<dyn Hittable>]) -> Self {
+        // Objects without a box (an empty mesh, say) cannot be placed in the
+        // tree, so they go in a plain list beside it.
+        objects.sort_by_key(|object| !has_box(object));
+        let bounded = objects.iter().filter(|object| has_box(object)).count();
+        if bounded < objects.len() {
+            let (bounded, unbounded) = objects.split_at_mut(bounded);
+            let right: Arc<dyn Hittable> = if bounded.is_empty() {
+                Arc::new(HittableList::new())
+            } else {
+                Arc::new(BvhNode::from_objects(bounded))
+            };
+            return Self {
+                left: Arc::new(HittableList {
+                    objects: unbounded.to_vec(),
+                }),
+                right,
+                bbox: None,
+            };
+        }
+
+        // Split along the axis on which the object centroids are most spread out.
+        let mut centroid_box = Aabb::default();
+        for (i, object) in objects.iter().enumerate() {
+            let c = object_box(object).centroid();
+            centroid_box = if i == 0 {
+                Aabb::new(&c, &c)
+            } else {
+                Aabb::surrounding_box(&centroid_box, &Aabb::new(&c, &c))
+            };
+        }
+        let axis = centroid_box.longest_axis();
+
+        let (left, right): (Arc<dyn Hittable>, Arc<dyn Hittable>) = match objects.len() {
+            0 => panic!("Cannot build a bvh node from an empty object list"),
+            1 => (objects[0].clone(), objects[0].clone()),
+            2 => {
+                if box_compare(&objects[0], &objects[1], axis) == Ordering::Less {
+                    (objects[0].clone(), objects[1].clone())
+                } else {
+                    (objects[1].clone(), objects[0].clone())
+                }
+            }
+            n => {
+                objects.sort_by(|a, b| box_compare(a, b, axis));
+
+                let mid = n / 2;
+                let (lower, upper) = objects.split_at_mut(mid);
+                (
+                    Arc::new(BvhNode::from_objects(lower)),
+                    Arc::new(BvhNode::from_objects(upper)),
+                )
+            }
+        };
+
+        let bbox = Some(Aabb::surrounding_box(
+            &object_box(&left),
+            &object_box(&right),
+        ));
+
+        Self { left, right, bbox }
+    }
+}
+
+fn has_box(object: &Arc<dyn Hittable>) -> bool {
+    object.bounding_box(&mut Aabb::default())
+}
+
+fn object_box(object: &Arc<dyn Hittable>) -> Aabb {
+    let mut output_box = Aabb::default();
+    if !object.bounding_box(&mut output_box) {
+        panic!("No bounding box in bvh node constructor");
+    }
+    output_box
+}
+
+fn box_compare(a: &Arc<dyn Hittable>, b: &Arc<dyn Hittable>, axis: usize) -> Ordering {
+    object_box(a).centroid()[axis].total_cmp(&object_box(b).centroid()[axis])
+}
+
+impl Hittable for BvhNode {
+    fn hit(
+        &self,
+        r: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rec: &mut HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> bool {
+        if let Some(bbox) = &self.bbox {
+            if !bbox.hit(r, t_min, t_max) {
+                return false;
+            }
+        }
+
+        let hit_left = self.left.hit(r, t_min, t_max, rec, sampler);
+        let hit_right =
+            self.right
+                .hit(r, t_min, if hit_left { rec.t } else { t_max }, rec, sampler);
+
+        hit_left || hit_right
+    }
+
+    fn bounding_box(&self, output_box: &mut Aabb) -> bool {
+        match self.bbox {
+            Some(bbox) => {
+                *output_box = bbox;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        material::lambertian::Lambertian,
+        model::{sphere::Sphere, vec3::Vec3},
+        sampler::independent::IndependentSampler,
+    };
+
+    fn sphere_row(n: usize) -> HittableList {
+        let material = Arc::new(Lambertian::new(&Vec3::new(0.5, 0.5, 0.5)));
+        let mut list = HittableList::new();
+        for i in 0..n {
+            list.add(Arc::new(Sphere::new(
+                Vec3::new(i as f64 * 3.0, 0.0, 0.0),
+                1.0,
+                material.clone(),
+            )));
+        }
+        list
+    }
+
+    #[test]
+    fn test_bvh_matches_list() {
+        let mut sampler = IndependentSampler::new(0);
+        let list = sphere_row(17);
+        let bvh = BvhNode::new(&list);
+
+        for i in 0..17 {
+            let r = Ray::new(
+                &Vec3::new(i as f64 * 3.0, 0.0, -10.0),
+                &Vec3::new(0.0, 0.0, 1.0),
+                0.0,
+            );
+            let mut list_rec = HitRecord::default();
+            let mut bvh_rec = HitRecord::default();
+            assert!(list.hit(&r, 0.001, f64::INFINITY, &mut list_rec, &mut sampler));
+            assert!(bvh.hit(&r, 0.001, f64::INFINITY, &mut bvh_rec, &mut sampler));
+            assert_eq!(list_rec.t, bvh_rec.t);
+            assert_eq!(list_rec.p, bvh_rec.p);
+        }
+    }
+
+    #[test]
+    fn test_bvh_miss() {
+        let mut sampler = IndependentSampler::new(0);
+        let bvh = BvhNode::new(&sphere_row(5));
+        let r = Ray::new(&Vec3::new(0.0, 5.0, -10.0), &Vec3::new(0.0, 0.0, 1.0), 0.0);
+        let mut rec = HitRecord::default();
+        assert!(!bvh.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+    }
+
+    #[test]
+    fn test_bvh_bounding_box() {
+        let bvh = BvhNode::new(&sphere_row(4));
+        let mut output_box = Aabb::default();
+        assert!(bvh.bounding_box(&mut output_box));
+        assert_eq!(Vec3::new(-1.0, -1.0, -1.0), *output_box.min());
+        assert_eq!(Vec3::new(10.0, 1.0, 1.0), *output_box.max());
+    }
+
+    #[test]
+    fn test_bvh_keeps_unbounded_objects() {
+        let mut sampler = IndependentSampler::new(0);
+        let mut list = sphere_row(3);
+        list.add(Arc::new(HittableList::new()));
+        let bvh = BvhNode::new(&list);
+
+        let r = Ray::new(&Vec3::new(6.0, 0.0, -10.0), &Vec3::new(0.0, 0.0, 1.0), 0.0);
+        let mut rec = HitRecord::default();
+        assert!(bvh.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+        assert_eq!(Vec3::new(6.0, 0.0, -1.0), rec.p);
+        assert!(!bvh.bounding_box(&mut Aabb::default()));
+
+        let empty = BvhNode::new(&HittableList {
+            objects: vec![Arc::new(HittableList::new())],
+        });
+        assert!(!empty.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+    }
+}
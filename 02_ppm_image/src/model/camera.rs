@@ -1,4 +1,4 @@
-use crate::util::rtweekend::degrees_to_radians;
+use crate::{sampler::Sampler, util::rtweekend::degrees_to_radians};
 
 use super::{ray::Ray, vec3::Vec3};
 
@@ -11,11 +11,13 @@ pub struct Camera {
     vertical: Vec3,
     u: Vec3,
     v: Vec3,
-    w: Vec3,
     lens_radius: f64,
+    time0: f64, // shutter open/close times
+    time1: f64,
 }
 
 impl Camera {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         lookfrom: &Point3,
         lookat: &Point3,
@@ -24,6 +26,8 @@ impl Camera {
         aspect_ratio: f64,
         aperture: f64,
         focus_dist: f64,
+        time0: f64,
+        time1: f64,
     ) -> Self {
         let theta = degrees_to_radians(vfov);
         let h = (theta / 2.0).tan();
@@ -34,7 +38,7 @@ impl Camera {
         let u = (vup.cross(&w)).unit_vector();
         let v = w.cross(&u);
 
-        let origin = lookfrom.clone();
+        let origin = *lookfrom;
         let horizontal = focus_dist * viewport_width * u;
         let vertical = focus_dist * viewport_height * v;
         let lower_left_corner = origin - horizontal / 2.0 - vertical / 2.0 - focus_dist * w;
@@ -46,13 +50,14 @@ impl Camera {
             lower_left_corner,
             u,
             v,
-            w,
             lens_radius,
+            time0,
+            time1,
         }
     }
 
-    pub fn get_ray(&self, s: f64, t: f64) -> Ray {
-        let rd = self.lens_radius * Vec3::random_unit_disk();
+    pub fn get_ray(&self, s: f64, t: f64, sampler: &mut dyn Sampler) -> Ray {
+        let rd = self.lens_radius * Vec3::random_unit_disk(sampler);
         let offset = self.u * rd.x() + self.v * rd.y();
 
         Ray::new(
@@ -60,6 +65,7 @@ impl Camera {
             &(self.lower_left_corner + s * self.horizontal + t * self.vertical
                 - self.origin
                 - offset),
+            sampler.get_range(self.time0, self.time1),
         )
     }
 }
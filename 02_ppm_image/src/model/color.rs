@@ -3,26 +3,18 @@ use crate::util::rtweekend::clamp;
 use super::vec3::Vec3;
 
 pub trait Color {
-    fn as_color_repr(&self, samples_per_pixel: usize) -> String;
+    // Gamma-corrects for gamma=2.0 and quantizes each channel to 0..=255.
+    fn as_rgb8(&self) -> [u8; 3];
+
+    fn as_color_repr(&self) -> String {
+        let [r, g, b] = self.as_rgb8();
+        format!("{} {} {}\n", r, g, b)
+    }
 }
 
 impl Color for Vec3 {
-    fn as_color_repr(&self, samples_per_pixel: usize) -> String {
-        let mut r = self.x();
-        let mut g = self.y();
-        let mut b = self.z();
-
-        // Divide the color by the number of samples and gamma-correct for gamma=2.0
-        let scale = 1.0 / samples_per_pixel as f64;
-        r = (scale * r).sqrt();
-        g = (scale * g).sqrt();
-        b = (scale * b).sqrt();
-
-        format!(
-            "{} {} {}\n",
-            (256.0 * clamp(r, 0.0, 0.999)) as i32,
-            (256.0 * clamp(g, 0.0, 0.999)) as i32,
-            (256.0 * clamp(b, 0.0, 0.999)) as i32,
-        )
+    fn as_rgb8(&self) -> [u8; 3] {
+        let encode = |c: f64| (256.0 * clamp(c.sqrt(), 0.0, 0.999)) as u8;
+        [encode(self.x()), encode(self.y()), encode(self.z())]
     }
 }
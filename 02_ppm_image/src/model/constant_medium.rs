@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use crate::{
+    material::{isotropic::Isotropic, material::Material},
+    sampler::Sampler,
+    util::rtweekend::INFINITY,
+};
+
+use super::{
+    aabb::Aabb,
+    hit::{HitRecord, Hittable},
+    ray::Ray,
+    vec3::Vec3,
+};
+
+// A volume of uniform density (smoke, fog) filling a closed, convex boundary.
+// A ray inside the volume scatters after a random distance whose probability
+// falls off exponentially with the density.
+pub struct ConstantMedium {
+    boundary: Arc<dyn Hittable>,
+    density: f64,
+    phase_function: Arc<dyn Material>,
+}
+
+impl ConstantMedium {
+    pub fn new(boundary: Arc<dyn Hittable>, density: f64, albedo: &Vec3) -> Self {
+        Self {
+            boundary,
+            density,
+            phase_function: Arc::new(Isotropic::new(albedo)),
+        }
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit(
+        &self,
+        r: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rec: &mut HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> bool {
+        if self.density <= 0.0 {
+            return false;
+        }
+
+        // Find where the ray enters and leaves the boundary, even if the ray
+        // starts inside it.
+        let mut rec1 = HitRecord::default();
+        let mut rec2 = HitRecord::default();
+
+        if !self
+            .boundary
+            .hit(r, -INFINITY, INFINITY, &mut rec1, sampler)
+        {
+            return false;
+        }
+        if !self
+            .boundary
+            .hit(r, rec1.t + 0.0001, INFINITY, &mut rec2, sampler)
+        {
+            return false;
+        }
+
+        rec1.t = rec1.t.max(t_min);
+        rec2.t = rec2.t.min(t_max);
+        if rec1.t >= rec2.t {
+            return false;
+        }
+        rec1.t = rec1.t.max(0.0);
+
+        let ray_length = r.dir().length();
+        let distance_inside_boundary = (rec2.t - rec1.t) * ray_length;
+        // 1 - u is never 0, so its logarithm stays finite.
+        let hit_distance = -(1.0 - sampler.get_independent_1d()).ln() / self.density;
+
+        if hit_distance > distance_inside_boundary {
+            return false;
+        }
+
+        rec.t = rec1.t + hit_distance / ray_length;
+        rec.p = r.at(rec.t);
+        rec.normal = Vec3::new(1.0, 0.0, 0.0); // arbitrary
+        rec.front_face = true; // also arbitrary
+        rec.material = self.phase_function.clone();
+
+        true
+    }
+
+    fn bounding_box(&self, output_box: &mut Aabb) -> bool {
+        self.boundary.bounding_box(output_box)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        material::lambertian::Lambertian,
+        model::{sphere::Sphere, transform::Transform},
+        sampler::independent::IndependentSampler,
+    };
+
+    use Vec3 as Point3;
+
+    fn fog(density: f64) -> ConstantMedium {
+        let boundary = Arc::new(Sphere::new(
+            Point3::new(0.0, 0.0, 0.0),
+            1.0,
+            Arc::new(Lambertian::new(&Vec3::new(0.5, 0.5, 0.5))),
+        ));
+        ConstantMedium::new(boundary, density, &Vec3::new(0.8, 0.8, 0.8))
+    }
+
+    #[test]
+    fn test_dense_medium_scatters_inside_boundary() {
+        let mut sampler = IndependentSampler::new(0);
+        let medium = fog(1000.0);
+        let r = Ray::new(&Point3::new(0.0, 0.0, -5.0), &Vec3::new(0.0, 0.0, 1.0), 0.0);
+        let mut rec = HitRecord::default();
+        for _ in 0..100 {
+            assert!(medium.hit(&r, 0.001, INFINITY, &mut rec, &mut sampler));
+            assert!(rec.t >= 4.0 && rec.t < 4.1);
+            assert!(rec.p.length() <= 1.0);
+        }
+
+        // A ray starting inside scatters ahead of its origin.
+        let inside = Ray::new(&Point3::default(), &Vec3::new(0.0, 0.0, 1.0), 0.0);
+        assert!(medium.hit(&inside, 0.001, INFINITY, &mut rec, &mut sampler));
+        assert!(rec.t > 0.0 && rec.t < 1.0);
+
+        // Instances of the medium scatter where their boundary is.
+        let moved = Transform::translate(Arc::new(fog(1000.0)), &Vec3::new(0.0, 0.0, 2.0));
+        assert!(moved.hit(&r, 0.001, INFINITY, &mut rec, &mut sampler));
+        assert!(rec.t >= 6.0 && rec.t < 6.1);
+    }
+
+    #[test]
+    fn test_empty_medium_never_scatters() {
+        let mut sampler = IndependentSampler::new(0);
+        let medium = fog(0.0);
+        let r = Ray::new(&Point3::new(0.0, 0.0, -5.0), &Vec3::new(0.0, 0.0, 1.0), 0.0);
+        let mut rec = HitRecord::default();
+        for _ in 0..100 {
+            assert!(!medium.hit(&r, 0.001, INFINITY, &mut rec, &mut sampler));
+        }
+    }
+
+    #[test]
+    fn test_medium_draws_leave_dimensions_alone() {
+        // The scattering distance comes from the independent stream, so the
+        // dimensions drawn after a medium hit are the same as without it.
+        let medium = fog(1000.0);
+        let r = Ray::new(&Point3::new(0.0, 0.0, -5.0), &Vec3::new(0.0, 0.0, 1.0), 0.0);
+        let mut sampler = IndependentSampler::new(7);
+        let mut reference = IndependentSampler::new(7);
+        sampler.start_pixel_sample(2, 3, 4);
+        reference.start_pixel_sample(2, 3, 4);
+
+        assert_eq!(reference.get_1d(), sampler.get_1d());
+        let mut rec = HitRecord::default();
+        assert!(medium.hit(&r, 0.001, INFINITY, &mut rec, &mut sampler));
+        assert_eq!(reference.get_2d(), sampler.get_2d());
+    }
+}
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use crate::{material::material::Material, sampler::Sampler};
+
+use super::{
+    aabb::Aabb,
+    hit::{HitRecord, Hittable, HittableList},
+    quad::Quad,
+    ray::Ray,
+    vec3::Vec3,
+};
+
+use Vec3 as Point3;
+
+// An axis-aligned box made of six outward-facing quads. Rotate or move it
+// with a Transform.
+pub struct Cuboid {
+    sides: HittableList,
+    bbox: Aabb,
+}
+
+impl Cuboid {
+    // Builds the box spanned by two opposite corners a and b.
+    pub fn new(a: &Point3, b: &Point3, m: Arc<dyn Material>) -> Self {
+        let bbox = Aabb::new(a, b);
+        let min = *bbox.min();
+        let max = *bbox.max();
+
+        let dx = Vec3::new(max.x() - min.x(), 0.0, 0.0);
+        let dy = Vec3::new(0.0, max.y() - min.y(), 0.0);
+        let dz = Vec3::new(0.0, 0.0, max.z() - min.z());
+
+        // Each edge pair is ordered so that u x v points out of the box.
+        let mut sides = HittableList::new();
+        let faces = [
+            (Point3::new(min.x(), min.y(), max.z()), dx, dy), // front
+            (Point3::new(max.x(), min.y(), max.z()), -dz, dy), // right
+            (Point3::new(max.x(), min.y(), min.z()), -dx, dy), // back
+            (Point3::new(min.x(), min.y(), min.z()), dz, dy), // left
+            (Point3::new(min.x(), max.y(), max.z()), dx, -dz), // top
+            (Point3::new(min.x(), min.y(), min.z()), dx, dz), // bottom
+        ];
+        for (q, u, v) in faces {
+            sides.add(Arc::new(Quad::new(q, u, v, m.clone())));
+        }
+
+        Self { sides, bbox }
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hit(
+        &self,
+        r: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rec: &mut HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> bool {
+        self.sides.hit(r, t_min, t_max, rec, sampler)
+    }
+
+    fn bounding_box(&self, output_box: &mut Aabb) -> bool {
+        *output_box = self.bbox.pad(0.0001);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::lambertian::Lambertian, sampler::independent::IndependentSampler};
+
+    #[test]
+    fn test_cuboid_normals_face_outward() {
+        let mut sampler = IndependentSampler::new(0);
+        let cuboid = Cuboid::new(
+            &Point3::new(1.0, 1.0, 1.0),
+            &Point3::new(-1.0, -1.0, -1.0),
+            Arc::new(Lambertian::new(&Vec3::new(0.5, 0.5, 0.5))),
+        );
+
+        let axes = [
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        ];
+        for axis in axes.iter().flat_map(|a| [*a, -a]) {
+            let r = Ray::new(&(5.0 * axis), &-axis, 0.0);
+            let mut rec = HitRecord::default();
+            assert!(cuboid.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+            assert_eq!(4.0, rec.t);
+            assert!(rec.front_face, "side facing {} is inverted", axis);
+            assert_eq!(axis, rec.normal);
+        }
+    }
+}
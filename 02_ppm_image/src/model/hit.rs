@@ -1,8 +1,11 @@
 use std::sync::Arc;
 
-use crate::material::{lambertian::Lambertian, material::Material};
+use crate::{
+    material::{lambertian::Lambertian, material::Material},
+    sampler::Sampler,
+};
 
-use super::{ray::Ray, vec3::Vec3};
+use super::{aabb::Aabb, ray::Ray, vec3::Vec3};
 use Vec3 as Point3;
 
 #[derive(Clone)]
@@ -11,6 +14,8 @@ pub struct HitRecord {
     pub normal: Vec3,
     pub material: Arc<dyn Material>,
     pub t: f64,
+    pub u: f64, // surface coordinates of the hit point
+    pub v: f64,
     pub front_face: bool,
 }
 
@@ -18,7 +23,7 @@ impl HitRecord {
     pub fn set_face_normal(&mut self, r: &Ray, outward_normal: &Vec3) {
         self.front_face = r.dir().dot(outward_normal) < 0.0;
         self.normal = if self.front_face {
-            outward_normal.clone()
+            *outward_normal
         } else {
             -outward_normal
         };
@@ -32,15 +37,44 @@ impl Default for HitRecord {
             normal: Default::default(),
             material: Arc::new(Lambertian::new(&Vec3::new(0.0, 0.0, 0.0))),
             t: Default::default(),
+            u: Default::default(),
+            v: Default::default(),
             front_face: Default::default(),
         }
     }
 }
 
-pub trait Hittable {
-    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool;
+pub trait Hittable: Send + Sync {
+    // `sampler` makes the random choices of objects whose hits are random,
+    // such as where a ray scatters inside a medium; containers pass it on.
+    fn hit(
+        &self,
+        r: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rec: &mut HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> bool;
+
+    // Writes the object's extent into output_box. Unbounded objects (planes,
+    // environment volumes) keep the default and report that they have no box.
+    fn bounding_box(&self, _output_box: &mut Aabb) -> bool {
+        false
+    }
+
+    // Density, per unit solid angle, with which `random(origin, sampler)` picks the
+    // direction `dir`. Objects that cannot be sampled as lights report zero.
+    fn pdf_value(&self, _origin: &Point3, _dir: &Vec3) -> f64 {
+        0.0
+    }
+
+    // A random direction from `origin` towards a point on the object.
+    fn random(&self, _origin: &Point3, _sampler: &mut dyn Sampler) -> Vec3 {
+        Vec3::new(1.0, 0.0, 0.0)
+    }
 }
 
+#[derive(Default)]
 pub struct HittableList {
     pub objects: Vec<Arc<dyn Hittable>>,
 }
@@ -62,13 +96,20 @@ impl HittableList {
 }
 
 impl Hittable for HittableList {
-    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+    fn hit(
+        &self,
+        r: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rec: &mut HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> bool {
         let mut temp_rec = HitRecord::default();
         let mut hit_anything = false;
         let mut closest_so_far = t_max;
 
         for object in self.objects.iter() {
-            if object.hit(r, t_min, closest_so_far, &mut temp_rec) {
+            if object.hit(r, t_min, closest_so_far, &mut temp_rec, sampler) {
                 hit_anything = true;
                 closest_so_far = temp_rec.t;
                 *rec = temp_rec.clone();
@@ -77,4 +118,46 @@ impl Hittable for HittableList {
 
         hit_anything
     }
+
+    fn bounding_box(&self, output_box: &mut Aabb) -> bool {
+        if self.objects.is_empty() {
+            return false;
+        }
+
+        let mut temp_box = Aabb::default();
+        let mut first_box = true;
+
+        for object in self.objects.iter() {
+            if !object.bounding_box(&mut temp_box) {
+                return false;
+            }
+            *output_box = if first_box {
+                temp_box
+            } else {
+                Aabb::surrounding_box(output_box, &temp_box)
+            };
+            first_box = false;
+        }
+
+        true
+    }
+
+    // Picks a member uniformly, so the density is the members' average.
+    fn pdf_value(&self, origin: &Point3, dir: &Vec3) -> f64 {
+        if self.objects.is_empty() {
+            return 0.0;
+        }
+
+        let weight = 1.0 / self.objects.len() as f64;
+        self.objects
+            .iter()
+            .map(|object| weight * object.pdf_value(origin, dir))
+            .sum()
+    }
+
+    fn random(&self, origin: &Point3, sampler: &mut dyn Sampler) -> Vec3 {
+        let n = self.objects.len();
+        let index = ((sampler.get_1d() * n as f64) as usize).min(n - 1);
+        self.objects[index].random(origin, sampler)
+    }
 }
@@ -0,0 +1,222 @@
+use std::ops::Mul;
+
+use crate::util::rtweekend::degrees_to_radians;
+
+use super::vec3::Vec3;
+
+use Vec3 as Point3;
+
+// Row-major 4x4 matrix for affine transforms of column vectors.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Mat4 {
+    m: [[f64; 4]; 4],
+}
+
+impl Mat4 {
+    pub fn new(m: [[f64; 4]; 4]) -> Self {
+        Self { m }
+    }
+
+    pub fn identity() -> Self {
+        Self::new([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn translate(offset: &Vec3) -> Self {
+        Self::new([
+            [1.0, 0.0, 0.0, offset.x()],
+            [0.0, 1.0, 0.0, offset.y()],
+            [0.0, 0.0, 1.0, offset.z()],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn scale(factors: &Vec3) -> Self {
+        Self::new([
+            [factors.x(), 0.0, 0.0, 0.0],
+            [0.0, factors.y(), 0.0, 0.0],
+            [0.0, 0.0, factors.z(), 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    // Counter-clockwise rotation (looking down the axis towards the origin)
+    // by `degrees` about an arbitrary axis through the origin.
+    pub fn rotate(axis: &Vec3, degrees: f64) -> Self {
+        let a = axis.unit_vector();
+        let (x, y, z) = (a.x(), a.y(), a.z());
+        let theta = degrees_to_radians(degrees);
+        let (sin_theta, cos_theta) = theta.sin_cos();
+        let t = 1.0 - cos_theta;
+
+        Self::new([
+            [
+                t * x * x + cos_theta,
+                t * x * y - sin_theta * z,
+                t * x * z + sin_theta * y,
+                0.0,
+            ],
+            [
+                t * x * y + sin_theta * z,
+                t * y * y + cos_theta,
+                t * y * z - sin_theta * x,
+                0.0,
+            ],
+            [
+                t * x * z - sin_theta * y,
+                t * y * z + sin_theta * x,
+                t * z * z + cos_theta,
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn transpose(&self) -> Mat4 {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.m[j][i];
+            }
+        }
+        Mat4::new(m)
+    }
+
+    // Gauss-Jordan elimination with partial pivoting. Returns None for
+    // singular matrices (e.g. a zero scale factor).
+    pub fn inverse(&self) -> Option<Mat4> {
+        let mut a = self.m;
+        let mut inv = Mat4::identity().m;
+
+        for col in 0..4 {
+            let pivot = (col..4)
+                .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+                .unwrap();
+            if a[pivot][col].abs() < 1e-12 {
+                return None;
+            }
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let scale = 1.0 / a[col][col];
+            for k in 0..4 {
+                a[col][k] *= scale;
+                inv[col][k] *= scale;
+            }
+
+            for row in 0..4 {
+                if row != col {
+                    let factor = a[row][col];
+                    for k in 0..4 {
+                        a[row][k] -= factor * a[col][k];
+                        inv[row][k] -= factor * inv[col][k];
+                    }
+                }
+            }
+        }
+
+        Some(Mat4::new(inv))
+    }
+
+    pub fn transform_point(&self, p: &Point3) -> Point3 {
+        let m = &self.m;
+        let x = m[0][0] * p.x() + m[0][1] * p.y() + m[0][2] * p.z() + m[0][3];
+        let y = m[1][0] * p.x() + m[1][1] * p.y() + m[1][2] * p.z() + m[1][3];
+        let z = m[2][0] * p.x() + m[2][1] * p.y() + m[2][2] * p.z() + m[2][3];
+        let w = m[3][0] * p.x() + m[3][1] * p.y() + m[3][2] * p.z() + m[3][3];
+        if w == 1.0 {
+            Point3::new(x, y, z)
+        } else {
+            Point3::new(x, y, z) / w
+        }
+    }
+
+    pub fn transform_vector(&self, v: &Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::new(
+            m[0][0] * v.x() + m[0][1] * v.y() + m[0][2] * v.z(),
+            m[1][0] * v.x() + m[1][1] * v.y() + m[1][2] * v.z(),
+            m[2][0] * v.x() + m[2][1] * v.y() + m[2][2] * v.z(),
+        )
+    }
+}
+
+impl Default for Mat4 {
+    fn default() -> Self {
+        Mat4::identity()
+    }
+}
+
+impl Mul for Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, other: Mat4) -> Mat4 {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.m[i][k] * other.m[k][j]).sum();
+            }
+        }
+        Mat4::new(m)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: &Vec3, b: &Vec3) {
+        assert!((a - b).length() < 1e-9, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_mat4_translate_point_and_vector() {
+        let m = Mat4::translate(&Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(
+            Point3::new(2.0, 3.0, 4.0),
+            m.transform_point(&Point3::new(1.0, 1.0, 1.0))
+        );
+        assert_eq!(
+            Vec3::new(1.0, 1.0, 1.0),
+            m.transform_vector(&Vec3::new(1.0, 1.0, 1.0))
+        );
+    }
+
+    #[test]
+    fn test_mat4_rotate() {
+        let m = Mat4::rotate(&Vec3::new(0.0, 1.0, 0.0), 90.0);
+        assert_near(
+            &Vec3::new(0.0, 0.0, -1.0),
+            &m.transform_vector(&Vec3::new(1.0, 0.0, 0.0)),
+        );
+        let m = Mat4::rotate(&Vec3::new(0.0, 0.0, 2.0), 90.0);
+        assert_near(
+            &Vec3::new(0.0, 1.0, 0.0),
+            &m.transform_vector(&Vec3::new(1.0, 0.0, 0.0)),
+        );
+    }
+
+    #[test]
+    fn test_mat4_inverse() {
+        let m = Mat4::translate(&Vec3::new(1.0, -2.0, 3.0))
+            * Mat4::rotate(&Vec3::new(1.0, 1.0, 0.0), 30.0)
+            * Mat4::scale(&Vec3::new(2.0, 0.5, 4.0));
+        let inv = m.inverse().unwrap();
+        let p = Point3::new(0.3, -1.7, 2.5);
+        assert_near(&p, &inv.transform_point(&m.transform_point(&p)));
+
+        let product = m * inv;
+        for i in 0..4 {
+            for j in 0..4 {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((product.m[i][j] - expected).abs() < 1e-9);
+            }
+        }
+
+        assert!(Mat4::scale(&Vec3::new(1.0, 0.0, 1.0)).inverse().is_none());
+    }
+}
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use crate::sampler::Sampler;
+
+use super::{
+    aabb::Aabb,
+    bvh::BvhNode,
+    hit::{HitRecord, Hittable},
+    ray::Ray,
+    triangle::Triangle,
+};
+
+// A triangle soup behind its own BVH, so a mesh of any size can be added to a
+// HittableList (or another BVH) as a single object.
+pub struct Mesh {
+    triangle_count: usize,
+    bvh: Option<BvhNode>,
+}
+
+impl Mesh {
+    pub fn new(triangles: Vec<Triangle>) -> Self {
+        let triangle_count = triangles.len();
+        let mut objects: Vec<Arc<dyn Hittable>> = triangles
+            .into_iter()
+            .map(|t| Arc::new(t) as Arc<dyn Hittable>)
+            .collect();
+
+        let bvh = if objects.is_empty() {
+            None
+        } else {
+            Some(BvhNode::from_objects(&mut objects))
+        };
+
+        Self {
+            triangle_count,
+            bvh,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.triangle_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triangle_count == 0
+    }
+}
+
+impl Hittable for Mesh {
+    fn hit(
+        &self,
+        r: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rec: &mut HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> bool {
+        match &self.bvh {
+            Some(bvh) => bvh.hit(r, t_min, t_max, rec, sampler),
+            None => false,
+        }
+    }
+
+    fn bounding_box(&self, output_box: &mut Aabb) -> bool {
+        match &self.bvh {
+            Some(bvh) => bvh.bounding_box(output_box),
+            None => false,
+        }
+    }
+}
@@ -1,6 +1,17 @@
+pub mod aabb;
+pub mod bvh;
 pub mod camera;
 pub mod color;
+pub mod constant_medium;
+pub mod cuboid;
 pub mod hit;
+pub mod mat4;
+pub mod mesh;
+pub mod moving_sphere;
+pub mod onb;
+pub mod quad;
 pub mod ray;
 pub mod sphere;
+pub mod transform;
+pub mod triangle;
 pub mod vec3;
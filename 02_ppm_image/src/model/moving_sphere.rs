@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use crate::{material::material::Material, sampler::Sampler};
+
+use super::{
+    aabb::Aabb,
+    hit::{HitRecord, Hittable},
+    ray::Ray,
+    sphere::Sphere,
+    vec3::Vec3,
+};
+
+use Vec3 as Point3;
+
+pub struct MovingSphere {
+    pub center0: Point3,
+    pub center1: Point3,
+    pub time0: f64,
+    pub time1: f64,
+    pub radius: f64,
+    pub material: Arc<dyn Material>,
+}
+
+impl MovingSphere {
+    pub fn new(
+        cen0: Point3,
+        cen1: Point3,
+        time0: f64,
+        time1: f64,
+        r: f64,
+        m: Arc<dyn Material>,
+    ) -> Self {
+        Self {
+            center0: cen0,
+            center1: cen1,
+            time0,
+            time1,
+            radius: r,
+            material: m,
+        }
+    }
+
+    pub fn center(&self, time: f64) -> Point3 {
+        if self.time1 == self.time0 {
+            return self.center0;
+        }
+        self.center0
+            + ((time - self.time0) / (self.time1 - self.time0)) * (self.center1 - self.center0)
+    }
+}
+
+impl Hittable for MovingSphere {
+    fn hit(
+        &self,
+        r: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rec: &mut HitRecord,
+        _sampler: &mut dyn Sampler,
+    ) -> bool {
+        Sphere::hit_at(
+            &self.center(r.time()),
+            self.radius,
+            &self.material,
+            r,
+            t_min,
+            t_max,
+            rec,
+        )
+    }
+
+    fn bounding_box(&self, output_box: &mut Aabb) -> bool {
+        // The box has to enclose the sphere over the whole shutter interval.
+        let r = Vec3::new(self.radius, self.radius, self.radius);
+        let box0 = Aabb::new(
+            &(self.center(self.time0) - r),
+            &(self.center(self.time0) + r),
+        );
+        let box1 = Aabb::new(
+            &(self.center(self.time1) - r),
+            &(self.center(self.time1) + r),
+        );
+        *output_box = Aabb::surrounding_box(&box0, &box1);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::lambertian::Lambertian, sampler::independent::IndependentSampler};
+
+    fn moving_sphere() -> MovingSphere {
+        MovingSphere::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.0, 2.0, 0.0),
+            0.0,
+            1.0,
+            0.5,
+            Arc::new(Lambertian::new(&Vec3::new(0.5, 0.5, 0.5))),
+        )
+    }
+
+    #[test]
+    fn test_moving_sphere_hit_follows_time() {
+        let mut sampler = IndependentSampler::new(0);
+        let sphere = moving_sphere();
+        let mut rec = HitRecord::default();
+        for (time, y) in [(0.0, 0.0), (1.0, 2.0)] {
+            let r = Ray::new(&Point3::new(0.0, y, -5.0), &Vec3::new(0.0, 0.0, 1.0), time);
+            assert!(sphere.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+            assert_eq!(4.5, rec.t);
+            assert_eq!(Vec3::new(0.0, 0.0, -1.0), rec.normal);
+
+            // The other end of the path is empty at this time.
+            let r = Ray::new(
+                &Point3::new(0.0, 2.0 - y, -5.0),
+                &Vec3::new(0.0, 0.0, 1.0),
+                time,
+            );
+            assert!(!sphere.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+        }
+    }
+
+    #[test]
+    fn test_moving_sphere_bounding_box() {
+        let mut output_box = Aabb::default();
+        assert!(moving_sphere().bounding_box(&mut output_box));
+        assert_eq!(Point3::new(-0.5, -0.5, -0.5), *output_box.min());
+        assert_eq!(Point3::new(0.5, 2.5, 0.5), *output_box.max());
+    }
+}
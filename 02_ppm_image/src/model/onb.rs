@@ -0,0 +1,58 @@
+use super::vec3::Vec3;
+
+// An orthonormal basis (u, v, w) built around a given w axis, used to turn
+// directions sampled about +z into directions about an arbitrary axis.
+pub struct Onb {
+    axis: [Vec3; 3],
+}
+
+impl Onb {
+    pub fn build_from_w(w: &Vec3) -> Self {
+        let w = w.unit_vector();
+        let a = if w.x().abs() > 0.9 {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let v = w.cross(&a).unit_vector();
+        let u = v.cross(&w);
+        Self { axis: [u, v, w] }
+    }
+
+    pub fn u(&self) -> &Vec3 {
+        &self.axis[0]
+    }
+
+    pub fn v(&self) -> &Vec3 {
+        &self.axis[1]
+    }
+
+    pub fn w(&self) -> &Vec3 {
+        &self.axis[2]
+    }
+
+    // Converts coordinates in this basis to world space.
+    pub fn local(&self, a: &Vec3) -> Vec3 {
+        a.x() * self.axis[0] + a.y() * self.axis[1] + a.z() * self.axis[2]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_onb_is_orthonormal() {
+        for w in [
+            Vec3::new(0.0, 0.0, 3.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(-1.0, 2.0, 0.5),
+        ] {
+            let onb = Onb::build_from_w(&w);
+            assert!(onb.u().dot(onb.v()).abs() < 1e-12);
+            assert!(onb.v().dot(onb.w()).abs() < 1e-12);
+            assert!((onb.u().cross(onb.v()) - *onb.w()).length() < 1e-12);
+            assert!((onb.local(&Vec3::new(0.0, 0.0, 1.0)) - w.unit_vector()).length() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::sync::Arc;
+
+use crate::{material::material::Material, sampler::Sampler, util::rtweekend::INFINITY};
+
+use super::{
+    aabb::Aabb,
+    hit::{HitRecord, Hittable},
+    ray::Ray,
+    vec3::Vec3,
+};
+
+use Vec3 as Point3;
+
+// A parallelogram spanned by the edges u and v from corner q. The hit point's
+// coordinates along u and v (each in [0,1]) become the record's UVs.
+pub struct Quad {
+    pub q: Point3,
+    pub u: Vec3,
+    pub v: Vec3,
+    pub material: Arc<dyn Material>,
+    normal: Vec3,
+    d: f64,  // plane equation: normal . p = d
+    w: Vec3, // cached n / (n . n), used to find planar coordinates
+    area: f64,
+}
+
+impl Quad {
+    pub fn new(q: Point3, u: Vec3, v: Vec3, m: Arc<dyn Material>) -> Self {
+        let n = u.cross(&v);
+        let normal = n.unit_vector();
+        Self {
+            q,
+            u,
+            v,
+            material: m,
+            normal,
+            d: normal.dot(&q),
+            w: n / n.dot(&n),
+            area: n.length(),
+        }
+    }
+
+    pub fn normal(&self) -> &Vec3 {
+        &self.normal
+    }
+
+    pub fn area(&self) -> f64 {
+        self.area
+    }
+
+    // The ray-quad intersection, shared with pdf_value, which has no sampler
+    // to pass to `hit`.
+    fn intersect(&self, r: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        let denom = self.normal.dot(r.dir());
+
+        // No hit if the ray is parallel to the plane.
+        if denom.abs() < 1e-8 {
+            return false;
+        }
+
+        let t = (self.d - self.normal.dot(r.origin())) / denom;
+        if t < t_min || t > t_max {
+            return false;
+        }
+
+        // Express the plane hit point in the (u, v) frame of the quad.
+        let intersection = r.at(t);
+        let planar_hitpt_vector = intersection - self.q;
+        let alpha = self.w.dot(&planar_hitpt_vector.cross(&self.v));
+        let beta = self.w.dot(&self.u.cross(&planar_hitpt_vector));
+
+        if !Quad::is_interior(alpha, beta) {
+            return false;
+        }
+
+        rec.t = t;
+        rec.p = intersection;
+        rec.u = alpha;
+        rec.v = beta;
+        rec.set_face_normal(r, &self.normal);
+        rec.material = self.material.clone();
+
+        true
+    }
+
+    fn is_interior(a: f64, b: f64) -> bool {
+        (0.0..=1.0).contains(&a) && (0.0..=1.0).contains(&b)
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(
+        &self,
+        r: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rec: &mut HitRecord,
+        _sampler: &mut dyn Sampler,
+    ) -> bool {
+        self.intersect(r, t_min, t_max, rec)
+    }
+
+    fn bounding_box(&self, output_box: &mut Aabb) -> bool {
+        let diagonal1 = Aabb::new(&self.q, &(self.q + self.u + self.v));
+        let diagonal2 = Aabb::new(&(self.q + self.u), &(self.q + self.v));
+        *output_box = Aabb::surrounding_box(&diagonal1, &diagonal2).pad(0.0001);
+        true
+    }
+
+    // Uniform over the quad's area, converted to solid angle:
+    // pdf = distance² / (|cos θ| · area).
+    fn pdf_value(&self, origin: &Point3, dir: &Vec3) -> f64 {
+        let mut rec = HitRecord::default();
+        if !self.intersect(&Ray::new(origin, dir, 0.0), 0.001, INFINITY, &mut rec) {
+            return 0.0;
+        }
+
+        let distance_squared = rec.t * rec.t * dir.length_squared();
+        let cosine = (dir.dot(&rec.normal) / dir.length()).abs();
+
+        distance_squared / (cosine * self.area)
+    }
+
+    fn random(&self, origin: &Point3, sampler: &mut dyn Sampler) -> Vec3 {
+        let (a, b) = sampler.get_2d();
+        let p = self.q + (a * self.u) + (b * self.v);
+        p - origin
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::lambertian::Lambertian, sampler::independent::IndependentSampler};
+
+    fn unit_quad() -> Quad {
+        Quad::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 4.0, 0.0),
+            Arc::new(Lambertian::new(&Vec3::new(0.5, 0.5, 0.5))),
+        )
+    }
+
+    #[test]
+    fn test_quad_hit_uv() {
+        let mut sampler = IndependentSampler::new(0);
+        let quad = unit_quad();
+        let r = Ray::new(&Point3::new(0.5, 3.0, 5.0), &Vec3::new(0.0, 0.0, -1.0), 0.0);
+        let mut rec = HitRecord::default();
+        assert!(quad.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+        assert_eq!(5.0, rec.t);
+        assert_eq!(0.25, rec.u);
+        assert_eq!(0.75, rec.v);
+        assert!(rec.front_face);
+        assert_eq!(Vec3::new(0.0, 0.0, 1.0), rec.normal);
+        assert_eq!(8.0, quad.area());
+    }
+
+    #[test]
+    fn test_quad_miss() {
+        let mut sampler = IndependentSampler::new(0);
+        let quad = unit_quad();
+        let mut rec = HitRecord::default();
+        let r = Ray::new(&Point3::new(2.5, 3.0, 5.0), &Vec3::new(0.0, 0.0, -1.0), 0.0);
+        assert!(!quad.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+
+        let r = Ray::new(&Point3::new(0.5, 3.0, 5.0), &Vec3::new(1.0, 0.0, 0.0), 0.0);
+        assert!(!quad.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+    }
+
+    #[test]
+    fn test_quad_light_sampling() {
+        let quad = unit_quad();
+        let origin = Point3::new(1.0, 2.0, 3.0);
+        let mut sampler = IndependentSampler::new(1);
+        for _ in 0..20 {
+            let dir = quad.random(&origin, &mut sampler);
+            let p = origin + dir;
+            assert_eq!(0.0, p.z());
+            assert!((0.0..=2.0).contains(&p.x()) && (0.0..=4.0).contains(&p.y()));
+
+            let cosine = 3.0 / dir.length();
+            let expected = dir.length_squared() / (cosine * 8.0);
+            assert!((quad.pdf_value(&origin, &dir) - expected).abs() < 1e-9);
+        }
+        assert_eq!(0.0, quad.pdf_value(&origin, &Vec3::new(0.0, 0.0, 1.0)));
+    }
+}
@@ -4,15 +4,18 @@ use Vec3 as Point3;
 pub struct Ray {
     origin: Point3,
     dir: Vec3,
+    time: f64,
 }
 
 impl Ray {
-    pub fn new(origin: &Point3, dir: &Vec3) -> Self {
+    pub fn new(origin: &Point3, dir: &Vec3, time: f64) -> Self {
         Self {
-            origin: origin.clone(),
-            dir: dir.clone(),
+            origin: *origin,
+            dir: *dir,
+            time,
         }
     }
+
     pub fn origin(&self) -> &Point3 {
         &self.origin
     }
@@ -21,6 +24,10 @@ impl Ray {
         &self.dir
     }
 
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
     pub fn at(&self, t: f64) -> Point3 {
         self.origin + t * self.dir
     }
@@ -1,8 +1,18 @@
 use std::sync::Arc;
 
-use crate::material::material::Material;
+use crate::{
+    material::material::Material,
+    sampler::Sampler,
+    util::rtweekend::{INFINITY, PI},
+};
 
-use super::{hit::Hittable, vec3::Vec3};
+use super::{
+    aabb::Aabb,
+    hit::{HitRecord, Hittable},
+    onb::Onb,
+    ray::Ray,
+    vec3::Vec3,
+};
 
 use Vec3 as Point3;
 
@@ -20,20 +30,34 @@ impl Sphere {
             material: m,
         }
     }
-}
 
-impl Hittable for Sphere {
-    fn hit(
-        &self,
-        r: &super::ray::Ray,
+    // Maps a point on the unit sphere to (u, v) in [0,1]^2: u is the angle
+    // around the Y axis from X=-1, v the angle from Y=-1 to Y=+1.
+    //     <1 0 0> yields <0.50 0.50>       <-1  0  0> yields <0.00 0.50>
+    //     <0 1 0> yields <0.50 1.00>       < 0 -1  0> yields <0.50 0.00>
+    //     <0 0 1> yields <0.25 0.50>       < 0  0 -1> yields <0.75 0.50>
+    pub fn get_sphere_uv(p: &Point3) -> (f64, f64) {
+        let theta = (-p.y()).acos();
+        let phi = (-p.z()).atan2(p.x()) + PI;
+
+        (phi / (2.0 * PI), theta / PI)
+    }
+
+    // The ray-sphere intersection for a sphere placed at `center`, shared
+    // with MovingSphere, which works out its center from the ray's time.
+    pub(crate) fn hit_at(
+        center: &Point3,
+        radius: f64,
+        material: &Arc<dyn Material>,
+        r: &Ray,
         t_min: f64,
         t_max: f64,
-        rec: &mut super::hit::HitRecord,
+        rec: &mut HitRecord,
     ) -> bool {
-        let oc = r.origin() - self.center;
+        let oc = r.origin() - center;
         let a = r.dir().length_squared();
         let half_b = oc.dot(r.dir());
-        let c = oc.length_squared() - self.radius * self.radius;
+        let c = oc.length_squared() - radius * radius;
 
         let discriminant = half_b * half_b - a * c;
         if discriminant < 0.0 {
@@ -52,10 +76,136 @@ impl Hittable for Sphere {
 
         rec.t = root;
         rec.p = r.at(rec.t);
-        let outward_normal = (rec.p - self.center) / self.radius;
+        let outward_normal = (rec.p - center) / radius;
         rec.set_face_normal(r, &outward_normal);
-        rec.material = self.material.clone();
+        (rec.u, rec.v) = Sphere::get_sphere_uv(&outward_normal);
+        rec.material = material.clone();
+
+        true
+    }
+
+    // A direction about +z inside the cone subtended by a sphere of the given
+    // radius whose center lies distance_squared away, uniform in solid angle.
+    fn random_to_sphere(radius: f64, distance_squared: f64, sampler: &mut dyn Sampler) -> Vec3 {
+        let (r1, r2) = sampler.get_2d();
+        let z = 1.0 + r2 * ((1.0 - radius * radius / distance_squared).sqrt() - 1.0);
+
+        let phi = 2.0 * PI * r1;
+        let x = phi.cos() * (1.0 - z * z).sqrt();
+        let y = phi.sin() * (1.0 - z * z).sqrt();
+
+        Vec3::new(x, y, z)
+    }
+}
+
+impl Hittable for Sphere {
+    fn hit(
+        &self,
+        r: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rec: &mut HitRecord,
+        _sampler: &mut dyn Sampler,
+    ) -> bool {
+        Sphere::hit_at(
+            &self.center,
+            self.radius,
+            &self.material,
+            r,
+            t_min,
+            t_max,
+            rec,
+        )
+    }
+
+    fn bounding_box(&self, output_box: &mut Aabb) -> bool {
+        let r = Vec3::new(self.radius, self.radius, self.radius);
+        *output_box = Aabb::new(&(self.center - r), &(self.center + r));
+        true
+    }
+
+    // Seen from outside, the sphere covers a cone of directions; sampling
+    // that cone uniformly gives a density of one over its solid angle.
+    fn pdf_value(&self, origin: &Point3, dir: &Vec3) -> f64 {
+        let mut rec = HitRecord::default();
+        let r = Ray::new(origin, dir, 0.0);
+        if !Sphere::hit_at(
+            &self.center,
+            self.radius,
+            &self.material,
+            &r,
+            0.001,
+            INFINITY,
+            &mut rec,
+        ) {
+            return 0.0;
+        }
+
+        let distance_squared = (self.center - origin).length_squared();
+        if distance_squared <= self.radius * self.radius {
+            return 0.0;
+        }
+        let cos_theta_max = (1.0 - self.radius * self.radius / distance_squared).sqrt();
+        let solid_angle = 2.0 * PI * (1.0 - cos_theta_max);
+
+        1.0 / solid_angle
+    }
 
-        return true;
+    fn random(&self, origin: &Point3, sampler: &mut dyn Sampler) -> Vec3 {
+        let direction = self.center - origin;
+        let uvw = Onb::build_from_w(&direction);
+        uvw.local(&Sphere::random_to_sphere(
+            self.radius,
+            direction.length_squared(),
+            sampler,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_sphere_uv() {
+        let cases = [
+            (Point3::new(1.0, 0.0, 0.0), (0.5, 0.5)),
+            (Point3::new(-1.0, 0.0, 0.0), (0.0, 0.5)),
+            (Point3::new(0.0, 1.0, 0.0), (0.5, 1.0)),
+            (Point3::new(0.0, -1.0, 0.0), (0.5, 0.0)),
+            (Point3::new(0.0, 0.0, 1.0), (0.25, 0.5)),
+            (Point3::new(0.0, 0.0, -1.0), (0.75, 0.5)),
+        ];
+        for (p, (u, v)) in cases {
+            let uv = Sphere::get_sphere_uv(&p);
+            assert!(
+                (uv.0 - u).abs() < 1e-12 && (uv.1 - v).abs() < 1e-12,
+                "{}",
+                p
+            );
+        }
+    }
+
+    #[test]
+    fn test_sphere_light_sampling() {
+        use crate::sampler::independent::IndependentSampler;
+
+        let sphere = Sphere::new(
+            Point3::new(0.0, 0.0, -10.0),
+            2.0,
+            Arc::new(crate::material::lambertian::Lambertian::new(
+                &Vec3::default(),
+            )),
+        );
+        let origin = Point3::default();
+        // cos(theta_max) = sqrt(1 - 4/100)
+        let expected = 1.0 / (2.0 * PI * (1.0 - 0.96f64.sqrt()));
+
+        let mut sampler = IndependentSampler::new(1);
+        for _ in 0..100 {
+            let dir = sphere.random(&origin, &mut sampler);
+            assert!((sphere.pdf_value(&origin, &dir) - expected).abs() < 1e-9);
+        }
+        assert_eq!(0.0, sphere.pdf_value(&origin, &Vec3::new(0.0, 0.0, 1.0)));
     }
 }
@@ -0,0 +1,280 @@
+use std::sync::Arc;
+
+use crate::sampler::Sampler;
+
+use super::{
+    aabb::Aabb,
+    hit::{HitRecord, Hittable},
+    mat4::Mat4,
+    ray::Ray,
+    vec3::Vec3,
+};
+
+use Vec3 as Point3;
+
+// Places any hittable in the world through an affine matrix. Rays are moved
+// into object space with the inverse, and hits are mapped back out; normals
+// go through the inverse transpose so non-uniform scales stay perpendicular.
+pub struct Transform {
+    object: Arc<dyn Hittable>,
+    matrix: Mat4,
+    inverse: Mat4,
+    normal_matrix: Mat4, // inverse transpose
+    // |det| of the linear part: how much the matrix scales volumes.
+    det: f64,
+    bbox: Option<Aabb>,
+}
+
+impl Transform {
+    // None if the matrix is singular (a zero scale, say).
+    pub fn new(object: Arc<dyn Hittable>, matrix: Mat4) -> Option<Self> {
+        let inverse = matrix.inverse()?;
+        Some(Transform::with_inverse(object, matrix, inverse))
+    }
+
+    pub fn translate(object: Arc<dyn Hittable>, offset: &Vec3) -> Self {
+        Transform::with_inverse(object, Mat4::translate(offset), Mat4::translate(&-offset))
+    }
+
+    pub fn rotate(object: Arc<dyn Hittable>, axis: &Vec3, degrees: f64) -> Self {
+        Transform::with_inverse(
+            object,
+            Mat4::rotate(axis, degrees),
+            Mat4::rotate(axis, -degrees),
+        )
+    }
+
+    // None if any factor is zero.
+    pub fn scale(object: Arc<dyn Hittable>, factors: &Vec3) -> Option<Self> {
+        Transform::new(object, Mat4::scale(factors))
+    }
+
+    fn with_inverse(object: Arc<dyn Hittable>, matrix: Mat4, inverse: Mat4) -> Self {
+        let mut object_box = Aabb::default();
+        let bbox = if object.bounding_box(&mut object_box) {
+            Some(transform_box(&matrix, &object_box))
+        } else {
+            None
+        };
+
+        let x = matrix.transform_vector(&Vec3::new(1.0, 0.0, 0.0));
+        let y = matrix.transform_vector(&Vec3::new(0.0, 1.0, 0.0));
+        let z = matrix.transform_vector(&Vec3::new(0.0, 0.0, 1.0));
+
+        Self {
+            object,
+            matrix,
+            normal_matrix: inverse.transpose(),
+            inverse,
+            det: x.dot(&y.cross(&z)).abs(),
+            bbox,
+        }
+    }
+
+    pub fn matrix(&self) -> &Mat4 {
+        &self.matrix
+    }
+}
+
+// The world-space box around all eight transformed corners.
+fn transform_box(matrix: &Mat4, object_box: &Aabb) -> Aabb {
+    let (min, max) = (object_box.min(), object_box.max());
+    let mut result: Option<Aabb> = None;
+
+    for i in 0..8 {
+        let corner = Point3::new(
+            if i & 1 == 0 { min.x() } else { max.x() },
+            if i & 2 == 0 { min.y() } else { max.y() },
+            if i & 4 == 0 { min.z() } else { max.z() },
+        );
+        let p = matrix.transform_point(&corner);
+        let point_box = Aabb::new(&p, &p);
+        result = Some(match result {
+            Some(b) => Aabb::surrounding_box(&b, &point_box),
+            None => point_box,
+        });
+    }
+
+    result.unwrap()
+}
+
+impl Hittable for Transform {
+    fn hit(
+        &self,
+        r: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rec: &mut HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> bool {
+        // The direction is deliberately left unnormalized so the ray parameter
+        // t means the same thing in both spaces.
+        let object_ray = Ray::new(
+            &self.inverse.transform_point(r.origin()),
+            &self.inverse.transform_vector(r.dir()),
+            r.time(),
+        );
+
+        if !self.object.hit(&object_ray, t_min, t_max, rec, sampler) {
+            return false;
+        }
+
+        rec.p = self.matrix.transform_point(&rec.p);
+        // The object already faced its normal against the object-space ray;
+        // the inverse transpose preserves the sign of that dot product.
+        rec.normal = self
+            .normal_matrix
+            .transform_vector(&rec.normal)
+            .unit_vector();
+
+        true
+    }
+
+    fn bounding_box(&self, output_box: &mut Aabb) -> bool {
+        match self.bbox {
+            Some(b) => {
+                *output_box = b;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Directions are sampled in object space and mapped out. A unit object
+    // direction d' lands on the world direction along M·d', and the matrix
+    // stretches solid angle about it by |det M| / |M·d'|³.
+    fn pdf_value(&self, origin: &Point3, dir: &Vec3) -> f64 {
+        let object_dir = self.inverse.transform_vector(dir).unit_vector();
+        let pdf = self
+            .object
+            .pdf_value(&self.inverse.transform_point(origin), &object_dir);
+        pdf * self.matrix.transform_vector(&object_dir).length().powi(3) / self.det
+    }
+
+    fn random(&self, origin: &Point3, sampler: &mut dyn Sampler) -> Vec3 {
+        let object_dir = self
+            .object
+            .random(&self.inverse.transform_point(origin), sampler);
+        self.matrix.transform_vector(&object_dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        material::lambertian::Lambertian,
+        model::{quad::Quad, sphere::Sphere},
+        sampler::independent::IndependentSampler,
+    };
+
+    fn unit_sphere() -> Arc<dyn Hittable> {
+        Arc::new(Sphere::new(
+            Point3::new(0.0, 0.0, 0.0),
+            1.0,
+            Arc::new(Lambertian::new(&Vec3::new(0.5, 0.5, 0.5))),
+        ))
+    }
+
+    #[test]
+    fn test_transform_translate() {
+        let mut sampler = IndependentSampler::new(0);
+        let moved = Transform::translate(unit_sphere(), &Vec3::new(5.0, 0.0, 0.0));
+        let r = Ray::new(
+            &Point3::new(5.0, 0.0, -10.0),
+            &Vec3::new(0.0, 0.0, 1.0),
+            0.0,
+        );
+        let mut rec = HitRecord::default();
+        assert!(moved.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+        assert!((rec.t - 9.0).abs() < 1e-9);
+        assert!((rec.p - Point3::new(5.0, 0.0, -1.0)).length() < 1e-9);
+        assert!((rec.normal - Vec3::new(0.0, 0.0, -1.0)).length() < 1e-9);
+
+        let r = Ray::new(
+            &Point3::new(0.0, 0.0, -10.0),
+            &Vec3::new(0.0, 0.0, 1.0),
+            0.0,
+        );
+        assert!(!moved.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+    }
+
+    #[test]
+    fn test_transform_non_uniform_scale() {
+        let mut sampler = IndependentSampler::new(0);
+        // An ellipsoid stretched along x; its normal at (2, 0, 0) still points
+        // along +x, and off-axis normals are not simply the scaled sphere ones.
+        let ellipsoid = Transform::scale(unit_sphere(), &Vec3::new(2.0, 1.0, 1.0)).unwrap();
+        let r = Ray::new(
+            &Point3::new(10.0, 0.0, 0.0),
+            &Vec3::new(-1.0, 0.0, 0.0),
+            0.0,
+        );
+        let mut rec = HitRecord::default();
+        assert!(ellipsoid.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+        assert!((rec.p - Point3::new(2.0, 0.0, 0.0)).length() < 1e-9);
+        assert!((rec.normal - Vec3::new(1.0, 0.0, 0.0)).length() < 1e-9);
+
+        let mut output_box = Aabb::default();
+        assert!(ellipsoid.bounding_box(&mut output_box));
+        assert_eq!(Point3::new(-2.0, -1.0, -1.0), *output_box.min());
+        assert_eq!(Point3::new(2.0, 1.0, 1.0), *output_box.max());
+    }
+
+    #[test]
+    fn test_transform_rotate_bounding_box() {
+        let rotated = Transform::rotate(
+            Arc::new(Transform::scale(unit_sphere(), &Vec3::new(2.0, 1.0, 1.0)).unwrap()),
+            &Vec3::new(0.0, 1.0, 0.0),
+            90.0,
+        );
+        let mut output_box = Aabb::default();
+        assert!(rotated.bounding_box(&mut output_box));
+        assert!((output_box.max().x() - 1.0).abs() < 1e-9);
+        assert!((output_box.max().z() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_transform_singular_matrix() {
+        assert!(Transform::scale(unit_sphere(), &Vec3::new(1.0, 0.0, 1.0)).is_none());
+        assert!(Transform::new(unit_sphere(), Mat4::scale(&Vec3::new(0.0, 0.0, 0.0))).is_none());
+    }
+
+    #[test]
+    fn test_transform_light_sampling() {
+        let material = Arc::new(Lambertian::new(&Vec3::new(0.5, 0.5, 0.5)));
+        let quad = |u: Vec3| {
+            Arc::new(Quad::new(
+                Point3::new(0.0, 0.0, 0.0),
+                u,
+                Vec3::new(0.0, 4.0, 0.0),
+                material.clone(),
+            ))
+        };
+        // A quad stretched and moved by the transform, and the same quad
+        // built directly in world space.
+        let stretched = Transform::new(
+            quad(Vec3::new(2.0, 0.0, 0.0)),
+            Mat4::translate(&Vec3::new(1.0, 0.0, 0.0)) * Mat4::scale(&Vec3::new(3.0, 1.0, 1.0)),
+        )
+        .unwrap();
+        let world = Transform::translate(quad(Vec3::new(6.0, 0.0, 0.0)), &Vec3::new(1.0, 0.0, 0.0));
+
+        let origin = Point3::new(2.0, 1.0, 5.0);
+        let mut sampler = IndependentSampler::new(3);
+        for _ in 0..16 {
+            let dir = stretched.random(&origin, &mut sampler);
+            let mut rec = HitRecord::default();
+            assert!(stretched.hit(
+                &Ray::new(&origin, &dir, 0.0),
+                0.001,
+                f64::INFINITY,
+                &mut rec,
+                &mut sampler
+            ));
+
+            let expected = world.pdf_value(&origin, &dir);
+            assert!((stretched.pdf_value(&origin, &dir) - expected).abs() < 1e-9 * expected);
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use std::sync::Arc;
+
+use crate::{material::material::Material, sampler::Sampler};
+
+use super::{
+    aabb::Aabb,
+    hit::{HitRecord, Hittable},
+    ray::Ray,
+    vec3::Vec3,
+};
+
+use Vec3 as Point3;
+
+pub struct Triangle {
+    pub vertices: [Point3; 3],
+    pub normals: Option<[Vec3; 3]>,   // per-vertex shading normals
+    pub uvs: Option<[(f64, f64); 3]>, // per-vertex texture coordinates
+    pub material: Arc<dyn Material>,
+}
+
+impl Triangle {
+    pub fn new(v0: Point3, v1: Point3, v2: Point3, m: Arc<dyn Material>) -> Self {
+        Self {
+            vertices: [v0, v1, v2],
+            normals: None,
+            uvs: None,
+            material: m,
+        }
+    }
+
+    pub fn with_normals(mut self, n0: Vec3, n1: Vec3, n2: Vec3) -> Self {
+        self.normals = Some([n0, n1, n2]);
+        self
+    }
+
+    pub fn with_uvs(mut self, uv0: (f64, f64), uv1: (f64, f64), uv2: (f64, f64)) -> Self {
+        self.uvs = Some([uv0, uv1, uv2]);
+        self
+    }
+
+    pub fn geometric_normal(&self) -> Vec3 {
+        let [p0, p1, p2] = self.vertices;
+        (p1 - p0).cross(&(p2 - p0)).unit_vector()
+    }
+}
+
+fn max_dimension(v: &Vec3) -> usize {
+    if v.x() > v.y() {
+        if v.x() > v.z() {
+            0
+        } else {
+            2
+        }
+    } else if v.y() > v.z() {
+        1
+    } else {
+        2
+    }
+}
+
+fn permute(v: &Vec3, x: usize, y: usize, z: usize) -> Vec3 {
+    Vec3::new(v[x], v[y], v[z])
+}
+
+impl Hittable for Triangle {
+    // Watertight ray/triangle test (Woop, Benthin and Wald, 2013). The ray is
+    // sheared into a space where it runs along +z from the origin, which turns
+    // the test into 2D edge functions that are evaluated identically for both
+    // triangles sharing an edge, so rays can never slip through a mesh seam.
+    fn hit(
+        &self,
+        r: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rec: &mut HitRecord,
+        _sampler: &mut dyn Sampler,
+    ) -> bool {
+        let [v0, v1, v2] = self.vertices;
+
+        // Translate the vertices so the ray origin is at (0, 0, 0).
+        let mut p0t = v0 - r.origin();
+        let mut p1t = v1 - r.origin();
+        let mut p2t = v2 - r.origin();
+
+        // Permute so the largest component of the direction becomes z.
+        let d_abs = Vec3::new(r.dir().x().abs(), r.dir().y().abs(), r.dir().z().abs());
+        let kz = max_dimension(&d_abs);
+        let kx = (kz + 1) % 3;
+        let ky = (kx + 1) % 3;
+        let d = permute(r.dir(), kx, ky, kz);
+        p0t = permute(&p0t, kx, ky, kz);
+        p1t = permute(&p1t, kx, ky, kz);
+        p2t = permute(&p2t, kx, ky, kz);
+
+        // Shear so the ray direction becomes +z. Only x and y are needed for
+        // the edge tests; z is scaled lazily once a hit is certain.
+        let sx = -d.x() / d.z();
+        let sy = -d.y() / d.z();
+        let sz = 1.0 / d.z();
+        let (p0x, p0y) = (p0t.x() + sx * p0t.z(), p0t.y() + sy * p0t.z());
+        let (p1x, p1y) = (p1t.x() + sx * p1t.z(), p1t.y() + sy * p1t.z());
+        let (p2x, p2y) = (p2t.x() + sx * p2t.z(), p2t.y() + sy * p2t.z());
+
+        // Edge functions.
+        let e0 = p1x * p2y - p1y * p2x;
+        let e1 = p2x * p0y - p2y * p0x;
+        let e2 = p0x * p1y - p0y * p1x;
+
+        if (e0 < 0.0 || e1 < 0.0 || e2 < 0.0) && (e0 > 0.0 || e1 > 0.0 || e2 > 0.0) {
+            return false;
+        }
+        let det = e0 + e1 + e2;
+        if det == 0.0 {
+            return false;
+        }
+
+        // Compare the scaled hit distance against the interval before dividing.
+        let p0z = sz * p0t.z();
+        let p1z = sz * p1t.z();
+        let p2z = sz * p2t.z();
+        let t_scaled = e0 * p0z + e1 * p1z + e2 * p2z;
+        if det < 0.0 && (t_scaled >= t_min * det || t_scaled < t_max * det) {
+            return false;
+        }
+        if det > 0.0 && (t_scaled <= t_min * det || t_scaled > t_max * det) {
+            return false;
+        }
+
+        let inv_det = 1.0 / det;
+        let b0 = e0 * inv_det;
+        let b1 = e1 * inv_det;
+        let b2 = e2 * inv_det;
+
+        rec.t = t_scaled * inv_det;
+        rec.p = b0 * v0 + b1 * v1 + b2 * v2;
+
+        let (u, v) = match self.uvs {
+            Some([uv0, uv1, uv2]) => (
+                b0 * uv0.0 + b1 * uv1.0 + b2 * uv2.0,
+                b0 * uv0.1 + b1 * uv1.1 + b2 * uv2.1,
+            ),
+            None => (b1, b2),
+        };
+        rec.u = u;
+        rec.v = v;
+
+        // The geometric normal decides which side was hit; an interpolated
+        // shading normal, if present, is then flipped onto that side.
+        let outward_normal = self.geometric_normal();
+        rec.set_face_normal(r, &outward_normal);
+        if let Some([n0, n1, n2]) = self.normals {
+            let shading_normal = (b0 * n0 + b1 * n1 + b2 * n2).unit_vector();
+            rec.normal = if shading_normal.dot(&rec.normal) < 0.0 {
+                -shading_normal
+            } else {
+                shading_normal
+            };
+        }
+        rec.material = self.material.clone();
+
+        true
+    }
+
+    fn bounding_box(&self, output_box: &mut Aabb) -> bool {
+        let [p0, p1, p2] = self.vertices;
+        let bbox = Aabb::surrounding_box(&Aabb::new(&p0, &p1), &Aabb::new(&p2, &p2));
+        *output_box = bbox.pad(0.0001);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::lambertian::Lambertian, sampler::independent::IndependentSampler};
+
+    fn material() -> Arc<dyn Material> {
+        Arc::new(Lambertian::new(&Vec3::new(0.5, 0.5, 0.5)))
+    }
+
+    fn unit_triangle() -> Triangle {
+        Triangle::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+            material(),
+        )
+    }
+
+    #[test]
+    fn test_triangle_hit() {
+        let mut sampler = IndependentSampler::new(0);
+        let tri = unit_triangle();
+        let r = Ray::new(
+            &Point3::new(0.25, 0.25, -2.0),
+            &Vec3::new(0.0, 0.0, 1.0),
+            0.0,
+        );
+        let mut rec = HitRecord::default();
+        assert!(tri.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+        assert!((rec.t - 2.0).abs() < 1e-12);
+        assert!((rec.p - Point3::new(0.25, 0.25, 0.0)).length() < 1e-12);
+        assert!((rec.u - 0.25).abs() < 1e-12);
+        assert!((rec.v - 0.25).abs() < 1e-12);
+        assert!(!rec.front_face);
+        assert_eq!(Vec3::new(0.0, 0.0, -1.0), rec.normal);
+    }
+
+    #[test]
+    fn test_triangle_miss() {
+        let mut sampler = IndependentSampler::new(0);
+        let tri = unit_triangle();
+        let mut rec = HitRecord::default();
+        let r = Ray::new(
+            &Point3::new(0.75, 0.75, -2.0),
+            &Vec3::new(0.0, 0.0, 1.0),
+            0.0,
+        );
+        assert!(!tri.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+
+        let r = Ray::new(
+            &Point3::new(0.25, 0.25, -2.0),
+            &Vec3::new(0.0, 0.0, 1.0),
+            0.0,
+        );
+        assert!(!tri.hit(&r, 0.001, 1.5, &mut rec, &mut sampler));
+
+        let r = Ray::new(
+            &Point3::new(0.25, 0.25, -2.0),
+            &Vec3::new(1.0, 0.0, 0.0),
+            0.0,
+        );
+        assert!(!tri.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+    }
+
+    #[test]
+    fn test_triangle_shared_edge_is_watertight() {
+        let mut sampler = IndependentSampler::new(0);
+        // Two triangles forming the unit square, split along its diagonal.
+        let a = unit_triangle();
+        let b = Triangle::new(
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+            material(),
+        );
+
+        for i in 1..100 {
+            let s = i as f64 / 100.0;
+            let r = Ray::new(
+                &Point3::new(s, 1.0 - s, -1.0),
+                &Vec3::new(0.0, 0.0, 1.0),
+                0.0,
+            );
+            let mut rec = HitRecord::default();
+            assert!(
+                a.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler)
+                    || b.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler)
+            );
+        }
+    }
+
+    #[test]
+    fn test_triangle_interpolated_attributes() {
+        let mut sampler = IndependentSampler::new(0);
+        let tri = unit_triangle()
+            .with_normals(
+                Vec3::new(0.0, 0.0, 1.0),
+                Vec3::new(1.0, 0.0, 1.0).unit_vector(),
+                Vec3::new(0.0, 1.0, 1.0).unit_vector(),
+            )
+            .with_uvs((0.0, 0.0), (2.0, 0.0), (0.0, 4.0));
+        let r = Ray::new(
+            &Point3::new(0.5, 0.25, 2.0),
+            &Vec3::new(0.0, 0.0, -1.0),
+            0.0,
+        );
+        let mut rec = HitRecord::default();
+        assert!(tri.hit(&r, 0.001, f64::INFINITY, &mut rec, &mut sampler));
+        assert!(rec.front_face);
+        assert!((rec.u - 1.0).abs() < 1e-12);
+        assert!((rec.v - 1.0).abs() < 1e-12);
+        assert!((rec.normal.length() - 1.0).abs() < 1e-12);
+        assert!(rec.normal.x() > 0.0 && rec.normal.y() > 0.0 && rec.normal.z() > 0.0);
+    }
+}
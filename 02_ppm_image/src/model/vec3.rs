@@ -1,9 +1,9 @@
 use std::{
     fmt::Display,
-    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    ops::{Add, AddAssign, Div, DivAssign, Index, Mul, MulAssign, Neg, Sub, SubAssign},
 };
 
-use crate::util::rtweekend::{random_double, random_double_by_range};
+use crate::{sampler::Sampler, util::rtweekend::PI};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Vec3 {
@@ -51,44 +51,49 @@ impl Vec3 {
         self / self.length()
     }
 
-    pub fn random() -> Vec3 {
-        Vec3::new(random_double(), random_double(), random_double())
+    pub fn random(sampler: &mut dyn Sampler) -> Vec3 {
+        Vec3::new(sampler.get_1d(), sampler.get_1d(), sampler.get_1d())
     }
 
-    pub fn random_by_range(min: f64, max: f64) -> Vec3 {
+    pub fn random_by_range(min: f64, max: f64, sampler: &mut dyn Sampler) -> Vec3 {
         Vec3::new(
-            random_double_by_range(min, max),
-            random_double_by_range(min, max),
-            random_double_by_range(min, max),
+            sampler.get_range(min, max),
+            sampler.get_range(min, max),
+            sampler.get_range(min, max),
         )
     }
 
-    pub fn random_in_unit_sphere() -> Vec3 {
-        loop {
-            let p = Vec3::random_by_range(-1.0, 1.0);
-            if p.length_squared() >= 1.0 {
-                continue;
-            }
-            return p;
-        }
+    // Uniform over the unit sphere.
+    pub fn random_unit_vector(sampler: &mut dyn Sampler) -> Vec3 {
+        let (u0, u1) = sampler.get_2d();
+        let z = 1.0 - 2.0 * u0;
+        let r = (1.0 - z * z).max(0.0).sqrt();
+        let phi = 2.0 * PI * u1;
+        Vec3::new(r * phi.cos(), r * phi.sin(), z)
     }
 
-    pub fn random_unit_vector() -> Vec3 {
-        Vec3::random_in_unit_sphere().unit_vector()
+    // Uniform over the unit disk in the xy plane. The concentric mapping
+    // keeps evenly spread samples evenly spread on the disk.
+    pub fn random_unit_disk(sampler: &mut dyn Sampler) -> Vec3 {
+        let (u0, u1) = sampler.get_2d();
+        let (a, b) = (2.0 * u0 - 1.0, 2.0 * u1 - 1.0);
+        if a == 0.0 && b == 0.0 {
+            return Vec3::default();
+        }
+        let (r, theta) = if a.abs() > b.abs() {
+            (a, PI / 4.0 * (b / a))
+        } else {
+            (b, PI / 2.0 - PI / 4.0 * (a / b))
+        };
+        Vec3::new(r * theta.cos(), r * theta.sin(), 0.0)
     }
 
-    pub fn random_unit_disk() -> Vec3 {
-        loop {
-            let p = Vec3::new(
-                random_double_by_range(-1.0, 1.0),
-                random_double_by_range(-1.0, 1.0),
-                0.0,
-            );
-            if p.length_squared() >= 1.0 {
-                continue;
-            }
-            return p;
-        }
+    // A unit vector about +z with density cos(θ)/π.
+    pub fn random_cosine_direction(sampler: &mut dyn Sampler) -> Vec3 {
+        let (u0, u1) = sampler.get_2d();
+        let phi = 2.0 * PI * u0;
+        let r = u1.sqrt();
+        Vec3::new(phi.cos() * r, phi.sin() * r, (1.0 - u1).sqrt())
     }
 
     pub fn near_zero(&self) -> bool {
@@ -96,6 +101,11 @@ impl Vec3 {
         self.e[0].abs() < s && self.e[1].abs() < s && self.e[2].abs() < s
     }
 
+    // Perceived brightness of a linear RGB color (Rec. 709 weights).
+    pub fn luminance(&self) -> f64 {
+        0.2126 * self.e[0] + 0.7152 * self.e[1] + 0.0722 * self.e[2]
+    }
+
     pub fn reflect(&self, n: &Vec3) -> Vec3 {
         self - 2.0 * self.dot(n) * n
     }
@@ -115,6 +125,14 @@ impl Default for Vec3 {
     }
 }
 
+impl Index<usize> for Vec3 {
+    type Output = f64;
+
+    fn index(&self, i: usize) -> &f64 {
+        &self.e[i]
+    }
+}
+
 impl Display for Vec3 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} {} {}", self.e[0], self.e[1], self.e[2])
@@ -367,4 +385,18 @@ mod tests {
         assert_eq!(1.0, a.y());
         assert_eq!(1.5, a.z());
     }
+
+    #[test]
+    fn test_random_directions() {
+        use crate::sampler::independent::IndependentSampler;
+
+        let mut sampler = IndependentSampler::new(9);
+        for _ in 0..100 {
+            assert!((Vec3::random_unit_vector(&mut sampler).length() - 1.0).abs() < 1e-12);
+            let d = Vec3::random_unit_disk(&mut sampler);
+            assert!(d.length_squared() <= 1.0 + 1e-12 && d.z() == 0.0);
+            let c = Vec3::random_cosine_direction(&mut sampler);
+            assert!((c.length() - 1.0).abs() < 1e-12 && c.z() >= 0.0);
+        }
+    }
 }
@@ -0,0 +1,311 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+use crate::{
+    film::Film, filter::Filter, integrator::Integrator, model::camera::Camera, sampler::Sampler,
+    scene::Scene,
+};
+
+pub struct RenderSettings {
+    pub width: usize,
+    pub height: usize,
+    pub samples_per_pixel: usize,
+    // Edge length, in pixels, of the square tiles handed out to workers.
+    pub tile_size: usize,
+    pub threads: usize,
+}
+
+// A rectangle of pixels [x0, x1) × [y0, y1), with y = 0 the top row.
+struct Tile {
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+}
+
+fn tiles(width: usize, height: usize, size: usize) -> Vec<Tile> {
+    let size = size.max(1);
+    let mut tiles = Vec::new();
+    for y0 in (0..height).step_by(size) {
+        for x0 in (0..width).step_by(size) {
+            tiles.push(Tile {
+                x0,
+                y0,
+                x1: (x0 + size).min(width),
+                y1: (y0 + size).min(height),
+            });
+        }
+    }
+    tiles
+}
+
+// Renders the image on a pool of worker threads, each repeatedly taking the
+// next unrendered tile. Each thread works with its own copy of `sampler`, and
+// each tile splats its samples, weighted by `filter`, into a film of its own;
+// the tiles' films are merged in order at the end. A pixel's samples depend
+// only on the sampler's seed, so the image does too, whatever the thread
+// count. `progress(done, total)` is called on the calling thread as tiles
+// finish.
+pub fn render(
+    camera: &Camera,
+    scene: &Scene,
+    integrator: &dyn Integrator,
+    sampler: &dyn Sampler,
+    filter: &dyn Filter,
+    settings: &RenderSettings,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Film {
+    let (width, height) = (settings.width, settings.height);
+    let tiles = &tiles(width, height, settings.tile_size);
+    let next_tile = &AtomicUsize::new(0);
+    let mut tile_films = vec![None; tiles.len()];
+
+    thread::scope(|s| {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..settings.threads.max(1) {
+            let sender = sender.clone();
+            s.spawn(move || {
+                let mut sampler = sampler.clone_box();
+
+                loop {
+                    let index = next_tile.fetch_add(1, Ordering::Relaxed);
+                    let Some(tile) = tiles.get(index) else {
+                        break;
+                    };
+                    let film = render_tile(
+                        tile,
+                        camera,
+                        scene,
+                        integrator,
+                        sampler.as_mut(),
+                        filter,
+                        settings,
+                    );
+                    if sender.send((index, film)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        // Ends once every worker has finished and dropped its sender.
+        for (done, (index, film)) in receiver.iter().enumerate() {
+            tile_films[index] = Some(film);
+            progress(done + 1, tiles.len());
+        }
+    });
+
+    let mut film = Film::new(width, height);
+    for tile_film in tile_films.iter().flatten() {
+        film.merge(tile_film);
+    }
+    film
+}
+
+// Renders one tile's samples into a film covering every pixel they can reach.
+fn render_tile(
+    tile: &Tile,
+    camera: &Camera,
+    scene: &Scene,
+    integrator: &dyn Integrator,
+    sampler: &mut dyn Sampler,
+    filter: &dyn Filter,
+    settings: &RenderSettings,
+) -> Film {
+    let (width, height) = (settings.width, settings.height);
+    let reach = filter.radius().ceil() as usize;
+    let mut film = Film::with_bounds(
+        tile.x0.saturating_sub(reach),
+        tile.y0.saturating_sub(reach),
+        tile.x1.saturating_add(reach).min(width),
+        tile.y1.saturating_add(reach).min(height),
+    );
+
+    for y in tile.y0..tile.y1 {
+        for x in tile.x0..tile.x1 {
+            for index in 0..settings.samples_per_pixel {
+                sampler.start_pixel_sample(x, y, index);
+                let (dx, dy) = sampler.get_2d();
+                let (film_x, film_y) = (x as f64 + dx, y as f64 + dy);
+                // The camera's t runs upwards from the bottom of the image.
+                let u = film_x / (width as f64 - 1.0);
+                let v = (height as f64 - film_y) / (height as f64 - 1.0);
+                let r = camera.get_ray(u, v, sampler);
+                let color = integrator.li(&r, scene, sampler);
+                film.add_sample(film_x, film_y, &color, filter);
+            }
+        }
+    }
+    film
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        background::{closure::FnBackground, gradient::GradientBackground, solid::SolidBackground},
+        filter::{box_filter::BoxFilter, mitchell::MitchellFilter},
+        integrator::path::PathTracer,
+        material::lambertian::Lambertian,
+        model::{hit::HittableList, sphere::Sphere, vec3::Vec3},
+        sampler::{
+            halton::HaltonSampler, independent::IndependentSampler, sobol::SobolSampler,
+            stratified::StratifiedSampler,
+        },
+    };
+
+    use Vec3 as Point3;
+
+    fn camera(aspect_ratio: f64) -> Camera {
+        Camera::new(
+            &Point3::new(0.0, 0.0, 0.0),
+            &Point3::new(0.0, 0.0, -1.0),
+            &Vec3::new(0.0, 1.0, 0.0),
+            90.0,
+            aspect_ratio,
+            0.0,
+            1.0,
+            0.0,
+            0.0,
+        )
+    }
+
+    #[test]
+    fn test_tiles_cover_image() {
+        let tiles = tiles(10, 7, 4);
+        assert_eq!(6, tiles.len());
+        let area: usize = tiles.iter().map(|t| (t.x1 - t.x0) * (t.y1 - t.y0)).sum();
+        assert_eq!(70, area);
+    }
+
+    #[test]
+    fn test_render_fills_every_pixel() {
+        let scene = Scene::new(
+            Arc::new(HittableList::new()),
+            Arc::new(SolidBackground::new(&Vec3::new(0.25, 0.5, 1.0))),
+        );
+        let integrator = PathTracer::new(3);
+        // A filter wider than the image must not overflow the tile bounds.
+        for (threads, radius) in [(1, 0.5), (3, 0.5), (2, 1e300)] {
+            let settings = RenderSettings {
+                width: 9,
+                height: 5,
+                samples_per_pixel: 4,
+                tile_size: 2,
+                threads,
+            };
+            let film = render(
+                &camera(9.0 / 5.0),
+                &scene,
+                &integrator,
+                &IndependentSampler::new(0),
+                &BoxFilter::new(radius),
+                &settings,
+                &mut |_, _| {},
+            );
+            assert_eq!((9, 5), (film.width(), film.height()));
+            for y in 0..5 {
+                for x in 0..9 {
+                    assert_eq!(Vec3::new(0.25, 0.5, 1.0), film.pixel(x, y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_keeps_top_row_first() {
+        // Bright above the horizon, dark below.
+        let scene = Scene::new(
+            Arc::new(HittableList::new()),
+            Arc::new(FnBackground::new(|dir: &Vec3| {
+                if dir.y() > 0.0 {
+                    Vec3::new(1.0, 1.0, 1.0)
+                } else {
+                    Vec3::new(0.0, 0.0, 0.0)
+                }
+            })),
+        );
+        let settings = RenderSettings {
+            width: 4,
+            height: 4,
+            samples_per_pixel: 1,
+            tile_size: 3,
+            threads: 2,
+        };
+        let mut reports = Vec::new();
+        let film = render(
+            &camera(1.0),
+            &scene,
+            &PathTracer::new(3),
+            &IndependentSampler::new(0),
+            &BoxFilter::new(0.5),
+            &settings,
+            &mut |done, total| reports.push((done, total)),
+        );
+        assert_eq!(Vec3::new(1.0, 1.0, 1.0), film.pixel(0, 0));
+        assert_eq!(Vec3::new(0.0, 0.0, 0.0), film.pixel(3, 3));
+        assert_eq!(vec![(1, 4), (2, 4), (3, 4), (4, 4)], reports);
+    }
+
+    #[test]
+    fn test_render_is_reproducible_across_thread_counts() {
+        let scene = Scene::new(
+            Arc::new(Sphere::new(
+                Point3::new(0.0, 0.0, -2.0),
+                1.0,
+                Arc::new(Lambertian::new(&Vec3::new(0.5, 0.5, 0.5))),
+            )),
+            Arc::new(GradientBackground::sky()),
+        );
+        let render_with = |threads, sampler: &dyn Sampler| {
+            let settings = RenderSettings {
+                width: 8,
+                height: 6,
+                samples_per_pixel: 4,
+                tile_size: 3,
+                threads,
+            };
+            render(
+                &camera(8.0 / 6.0),
+                &scene,
+                &PathTracer::new(3),
+                sampler,
+                &MitchellFilter::new(2.0),
+                &settings,
+                &mut |_, _| {},
+            )
+        };
+
+        let samplers: [(Box<dyn Sampler>, Box<dyn Sampler>); 4] = [
+            (
+                Box::new(IndependentSampler::new(7)),
+                Box::new(IndependentSampler::new(8)),
+            ),
+            (
+                Box::new(StratifiedSampler::new(4, 7)),
+                Box::new(StratifiedSampler::new(4, 8)),
+            ),
+            (
+                Box::new(HaltonSampler::new(7)),
+                Box::new(HaltonSampler::new(8)),
+            ),
+            (
+                Box::new(SobolSampler::new(7)),
+                Box::new(SobolSampler::new(8)),
+            ),
+        ];
+        for (sampler, reseeded) in samplers {
+            let image = render_with(1, sampler.as_ref());
+            assert_eq!(image, render_with(4, sampler.as_ref()));
+            assert_ne!(image, render_with(1, reseeded.as_ref()));
+        }
+    }
+}
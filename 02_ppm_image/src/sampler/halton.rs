@@ -0,0 +1,136 @@
+use crate::util::rng::{hash_seed, permutation_element, Pcg32};
+
+use super::{independent_stream, Sampler, ONE_MINUS_EPSILON};
+
+// Dimensions with a Halton base of their own; later ones fall back to
+// independent random values.
+const DIMENSIONS: usize = 128;
+const PRIMES: [u64; DIMENSIONS] = first_primes();
+
+const fn first_primes() -> [u64; DIMENSIONS] {
+    let mut primes = [0; DIMENSIONS];
+    let mut count = 0;
+    let mut candidate = 2;
+    while count < DIMENSIONS {
+        let mut divisor = 2;
+        let mut is_prime = true;
+        while divisor * divisor <= candidate {
+            if candidate % divisor == 0 {
+                is_prime = false;
+                break;
+            }
+            divisor += 1;
+        }
+        if is_prime {
+            primes[count] = candidate;
+            count += 1;
+        }
+        candidate += 1;
+    }
+    primes
+}
+
+// The Halton sequence: dimension d of sample i is the radical inverse of i in
+// the d-th prime base, so every dimension fills [0,1) evenly. Digits are
+// Owen-scrambled per pixel and dimension, which breaks up the patterns the
+// plain sequence shows between neighbouring pixels and between high bases.
+#[derive(Clone)]
+pub struct HaltonSampler {
+    seed: u64,
+    pixel_seed: u64,
+    index: u64,
+    dimension: usize,
+    rng: Pcg32,
+    independent: Pcg32,
+}
+
+impl HaltonSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            pixel_seed: seed,
+            index: 0,
+            dimension: 0,
+            rng: Pcg32::new(seed, 0),
+            independent: Pcg32::new(seed, 1),
+        }
+    }
+}
+
+impl Sampler for HaltonSampler {
+    fn start_pixel_sample(&mut self, x: usize, y: usize, index: usize) {
+        self.pixel_seed = hash_seed(&[self.seed, x as u64, y as u64]);
+        self.index = index as u64;
+        self.dimension = 0;
+        self.rng = Pcg32::new(hash_seed(&[self.pixel_seed, self.index]), 0);
+        self.independent = independent_stream(self.seed, x, y, index);
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        let dimension = self.dimension;
+        self.dimension += 1;
+        if dimension >= DIMENSIONS {
+            return self.rng.next_f64();
+        }
+        let seed = hash_seed(&[self.pixel_seed, dimension as u64]);
+        owen_scrambled_radical_inverse(PRIMES[dimension], self.index, seed)
+    }
+
+    fn get_independent_1d(&mut self) -> f64 {
+        self.independent.next_f64()
+    }
+
+    fn clone_box(&self) -> Box<dyn Sampler> {
+        Box::new(self.clone())
+    }
+}
+
+// The digits of `a` in `base`, mirrored about the radix point, with each digit
+// permuted by a hash of the digits before it. Digits continue past the last
+// non-zero one so that the scrambling reaches full precision.
+fn owen_scrambled_radical_inverse(base: u64, mut a: u64, seed: u64) -> f64 {
+    let inv_base = 1.0 / base as f64;
+    let mut inv_base_m = 1.0;
+    let mut reversed_digits: u64 = 0;
+    while 1.0 - inv_base_m < 1.0 {
+        let next = a / base;
+        let digit = a - next * base;
+        let digit_seed = hash_seed(&[seed, reversed_digits]) as u32;
+        let digit = permutation_element(digit as u32, base as u32, digit_seed) as u64;
+        reversed_digits = reversed_digits * base + digit;
+        inv_base_m *= inv_base;
+        a = next;
+    }
+    (reversed_digits as f64 * inv_base_m).min(ONE_MINUS_EPSILON)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_primes() {
+        assert_eq!([2, 3, 5, 7, 11, 13], PRIMES[..6]);
+        assert_eq!(719, PRIMES[DIMENSIONS - 1]);
+    }
+
+    #[test]
+    fn test_dimensions_are_stratified() {
+        // 8 samples fill the eighths in base 2 and 9 the ninths in base 3.
+        let mut sampler = HaltonSampler::new(11);
+        let mut seen_2 = [false; 8];
+        let mut seen_3 = [false; 9];
+        for index in 0..9 {
+            sampler.start_pixel_sample(4, 7, index);
+            let (u, v) = sampler.get_2d();
+            if index < 8 {
+                let i = (u * 8.0) as usize;
+                assert!(!seen_2[i]);
+                seen_2[i] = true;
+            }
+            let j = (v * 9.0) as usize;
+            assert!(!seen_3[j]);
+            seen_3[j] = true;
+        }
+    }
+}
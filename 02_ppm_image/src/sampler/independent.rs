@@ -0,0 +1,40 @@
+use crate::util::rng::{hash_seed, Pcg32};
+
+use super::{independent_stream, Sampler};
+
+// Every value is an independent uniform random number.
+#[derive(Clone)]
+pub struct IndependentSampler {
+    seed: u64,
+    rng: Pcg32,
+    independent: Pcg32,
+}
+
+impl IndependentSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: Pcg32::new(seed, 0),
+            independent: Pcg32::new(seed, 1),
+        }
+    }
+}
+
+impl Sampler for IndependentSampler {
+    fn start_pixel_sample(&mut self, x: usize, y: usize, index: usize) {
+        self.rng = Pcg32::new(hash_seed(&[self.seed, x as u64, y as u64, index as u64]), 0);
+        self.independent = independent_stream(self.seed, x, y, index);
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        self.rng.next_f64()
+    }
+
+    fn get_independent_1d(&mut self) -> f64 {
+        self.independent.next_f64()
+    }
+
+    fn clone_box(&self) -> Box<dyn Sampler> {
+        Box::new(self.clone())
+    }
+}
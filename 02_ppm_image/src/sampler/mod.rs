@@ -0,0 +1,53 @@
+pub mod halton;
+pub mod independent;
+pub mod sobol;
+pub mod stratified;
+
+use crate::util::rng::{hash_seed, Pcg32};
+
+// A source of sample values in [0,1) for the random decisions made while
+// rendering a pixel: where in the pixel to look, where on the lens, which
+// light to aim at, which way to bounce. It is also the random number
+// generator passed to anything that needs one, so a render is reproducible
+// from its seed.
+//
+// Each value drawn for a pixel sample is a new dimension. The renderer takes
+// the pixel position from the first two, the camera the lens position and
+// time from the next three, and the integrator draws the rest bounce by
+// bounce. Choices whose number varies from sample to sample, like how far a
+// ray travels through a medium, draw from a separate stream instead so they
+// don't shift the dimensions every later draw lands on. Better-than-random
+// samplers spread each dimension's values evenly over a pixel's samples.
+pub trait Sampler: Send + Sync {
+    // Moves to sample `index` of pixel (x, y) and back to the first
+    // dimension. Values depend only on the seed, pixel, index and dimension,
+    // never on what was drawn before.
+    fn start_pixel_sample(&mut self, x: usize, y: usize, index: usize);
+
+    fn get_1d(&mut self) -> f64;
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        (self.get_1d(), self.get_1d())
+    }
+
+    // Uniform in [min, max).
+    fn get_range(&mut self, min: f64, max: f64) -> f64 {
+        min + (max - min) * self.get_1d()
+    }
+
+    // A uniform value in [0,1) from the sample's independent stream, outside
+    // the dimension layout above.
+    fn get_independent_1d(&mut self) -> f64;
+
+    // A sampler with the same settings, for another thread.
+    fn clone_box(&self) -> Box<dyn Sampler>;
+}
+
+// The generator behind `get_independent_1d` for sample `index` of pixel
+// (x, y): a PCG stream of its own, so it never repeats the sampler's values.
+pub(crate) fn independent_stream(seed: u64, x: usize, y: usize, index: usize) -> Pcg32 {
+    Pcg32::new(hash_seed(&[seed, x as u64, y as u64, index as u64]), 1)
+}
+
+// Largest f64 below 1, so scaled integers never round up to 1.
+pub(crate) const ONE_MINUS_EPSILON: f64 = 1.0 - f64::EPSILON / 2.0;
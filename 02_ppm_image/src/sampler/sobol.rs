@@ -0,0 +1,155 @@
+use crate::util::rng::{hash_seed, Pcg32};
+
+use super::{independent_stream, Sampler};
+
+// Owen-scrambled Sobol points, following Burley, "Practical Hash-based Owen
+// Scrambling" (2020). Every 2D value comes from the first two Sobol
+// dimensions, which are well stratified together; each request shuffles the
+// sample order and scrambles the point with seeds of its own, so dimensions
+// are uncorrelated and there is no limit on how many a path uses.
+#[derive(Clone)]
+pub struct SobolSampler {
+    seed: u64,
+    pixel_seed: u64,
+    index: u32,
+    dimension: u64,
+    independent: Pcg32,
+}
+
+impl SobolSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            pixel_seed: seed,
+            index: 0,
+            dimension: 0,
+            independent: Pcg32::new(seed, 1),
+        }
+    }
+
+    // This sample's index among a pixel's, shuffled for the next dimension.
+    fn next_index(&mut self) -> (u32, u64) {
+        let seed = hash_seed(&[self.pixel_seed, self.dimension]);
+        (nested_uniform_scramble(self.index, seed as u32), seed)
+    }
+}
+
+impl Sampler for SobolSampler {
+    fn start_pixel_sample(&mut self, x: usize, y: usize, index: usize) {
+        self.pixel_seed = hash_seed(&[self.seed, x as u64, y as u64]);
+        self.index = index as u32;
+        self.dimension = 0;
+        self.independent = independent_stream(self.seed, x, y, index);
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        let (index, seed) = self.next_index();
+        self.dimension += 1;
+        to_unit(nested_uniform_scramble(
+            sobol(index, 0),
+            hash_seed(&[seed, 0]) as u32,
+        ))
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        let (index, seed) = self.next_index();
+        self.dimension += 2;
+        (
+            to_unit(nested_uniform_scramble(
+                sobol(index, 0),
+                hash_seed(&[seed, 0]) as u32,
+            )),
+            to_unit(nested_uniform_scramble(
+                sobol(index, 1),
+                hash_seed(&[seed, 1]) as u32,
+            )),
+        )
+    }
+
+    fn get_independent_1d(&mut self) -> f64 {
+        self.independent.next_f64()
+    }
+
+    fn clone_box(&self) -> Box<dyn Sampler> {
+        Box::new(self.clone())
+    }
+}
+
+// Sobol dimension 0 (the van der Corput sequence) or 1, as a 32-bit fraction.
+fn sobol(index: u32, dimension: usize) -> u32 {
+    let mut v: u32 = 1 << 31;
+    let mut x = 0;
+    for bit in 0..32 {
+        if (index >> bit) & 1 != 0 {
+            x ^= v;
+        }
+        v = if dimension == 0 { v >> 1 } else { v ^ (v >> 1) };
+    }
+    x
+}
+
+// An Owen scramble of a 32-bit fraction: each bit is flipped or not depending
+// on a hash of the bits above it (Laine–Karras hash, done on reversed bits).
+fn nested_uniform_scramble(x: u32, seed: u32) -> u32 {
+    let mut x = x.reverse_bits();
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50b47c);
+    x ^= x.wrapping_mul(0xb82f1e52);
+    x ^= x.wrapping_mul(0xc7afe638);
+    x ^= x.wrapping_mul(0x8d22f6e6);
+    x.reverse_bits()
+}
+
+fn to_unit(x: u32) -> f64 {
+    x as f64 / (1u64 << 32) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sobol_dimension_one() {
+        let expected = [0x0, 0x80000000, 0xc0000000, 0x40000000, 0xa0000000];
+        for (i, e) in expected.into_iter().enumerate() {
+            assert_eq!(e, sobol(i as u32, 1));
+        }
+    }
+
+    #[test]
+    fn test_2d_values_are_stratified() {
+        // 16 samples put one point in each 4×4 cell, in every dimension.
+        let mut sampler = SobolSampler::new(5);
+        for skipped in 0..3 {
+            let mut seen = [false; 16];
+            for index in 0..16 {
+                sampler.start_pixel_sample(1, 2, index);
+                for _ in 0..skipped {
+                    sampler.get_2d();
+                }
+                let (u, v) = sampler.get_2d();
+                let cell = (u * 4.0) as usize + 4 * (v * 4.0) as usize;
+                assert!(!seen[cell]);
+                seen[cell] = true;
+            }
+        }
+    }
+
+    #[test]
+    fn test_independent_draws_keep_the_layout() {
+        // However many independent values are drawn in between, the 2D values
+        // land on the same dimensions.
+        let mut sampler = SobolSampler::new(5);
+        let mut reference = SobolSampler::new(5);
+        for index in 0..4 {
+            sampler.start_pixel_sample(1, 2, index);
+            reference.start_pixel_sample(1, 2, index);
+            for draws in 0..3 {
+                for _ in 0..draws {
+                    assert!((0.0..1.0).contains(&sampler.get_independent_1d()));
+                }
+                assert_eq!(reference.get_2d(), sampler.get_2d());
+            }
+        }
+    }
+}
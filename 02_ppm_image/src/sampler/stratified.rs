@@ -0,0 +1,106 @@
+use crate::util::rng::{hash_seed, permutation_element, Pcg32};
+
+use super::{independent_stream, Sampler, ONE_MINUS_EPSILON};
+
+// Splits each dimension into as many strata as there are samples per pixel
+// (a grid of them for 2D values) and puts each sample in a different one,
+// jittered within it. Which sample gets which stratum is shuffled per pixel
+// and dimension so that dimensions stay uncorrelated.
+#[derive(Clone)]
+pub struct StratifiedSampler {
+    samples_per_pixel: usize,
+    x_strata: usize,
+    y_strata: usize,
+    seed: u64,
+    pixel_seed: u64,
+    index: usize,
+    dimension: u64,
+    rng: Pcg32,
+    independent: Pcg32,
+}
+
+impl StratifiedSampler {
+    pub fn new(samples_per_pixel: usize, seed: u64) -> Self {
+        let samples_per_pixel = samples_per_pixel.max(1);
+        // The squarest grid with at least one stratum per sample.
+        let x_strata = (samples_per_pixel as f64).sqrt() as usize;
+        let y_strata = samples_per_pixel.div_ceil(x_strata);
+        Self {
+            samples_per_pixel,
+            x_strata,
+            y_strata,
+            seed,
+            pixel_seed: seed,
+            index: 0,
+            dimension: 0,
+            rng: Pcg32::new(seed, 0),
+            independent: Pcg32::new(seed, 1),
+        }
+    }
+
+    // The stratum, out of `n`, that the current sample takes in the next
+    // dimension.
+    fn next_stratum(&mut self, n: usize) -> usize {
+        let seed = hash_seed(&[self.pixel_seed, self.dimension]) as u32;
+        permutation_element((self.index % n) as u32, n as u32, seed) as usize
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn start_pixel_sample(&mut self, x: usize, y: usize, index: usize) {
+        self.pixel_seed = hash_seed(&[self.seed, x as u64, y as u64]);
+        self.index = index;
+        self.dimension = 0;
+        self.rng = Pcg32::new(hash_seed(&[self.pixel_seed, index as u64]), 0);
+        self.independent = independent_stream(self.seed, x, y, index);
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        let n = self.samples_per_pixel;
+        let stratum = self.next_stratum(n);
+        self.dimension += 1;
+        ((stratum as f64 + self.rng.next_f64()) / n as f64).min(ONE_MINUS_EPSILON)
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        let stratum = self.next_stratum(self.x_strata * self.y_strata);
+        self.dimension += 2;
+        let (sx, sy) = (stratum % self.x_strata, stratum / self.x_strata);
+        (
+            ((sx as f64 + self.rng.next_f64()) / self.x_strata as f64).min(ONE_MINUS_EPSILON),
+            ((sy as f64 + self.rng.next_f64()) / self.y_strata as f64).min(ONE_MINUS_EPSILON),
+        )
+    }
+
+    fn get_independent_1d(&mut self) -> f64 {
+        self.independent.next_f64()
+    }
+
+    fn clone_box(&self) -> Box<dyn Sampler> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one_sample_per_stratum() {
+        let n = 10;
+        let mut sampler = StratifiedSampler::new(n, 3);
+        let mut seen_1d = vec![false; n];
+        let mut seen_2d = [false; 3 * 4];
+        for index in 0..n {
+            sampler.start_pixel_sample(5, 2, index);
+            let stratum = (sampler.get_1d() * n as f64) as usize;
+            assert!(!seen_1d[stratum]);
+            seen_1d[stratum] = true;
+
+            let (u, v) = sampler.get_2d();
+            let cell = (u * 3.0) as usize + 3 * (v * 4.0) as usize;
+            assert!(!seen_2d[cell]);
+            seen_2d[cell] = true;
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use crate::{
+    background::Background,
+    model::hit::{Hittable, HittableList},
+};
+
+// Everything a render needs besides the camera: the geometry, what lies
+// beyond it, and which emitters to aim shadow rays at.
+pub struct Scene {
+    pub world: Arc<dyn Hittable>,
+    pub background: Arc<dyn Background>,
+    // Emitting objects that are also part of `world`, sampled directly at
+    // each diffuse hit. Emitters left out are still found by chance.
+    pub lights: HittableList,
+}
+
+impl Scene {
+    pub fn new(world: Arc<dyn Hittable>, background: Arc<dyn Background>) -> Self {
+        Self {
+            world,
+            background,
+            lights: HittableList::new(),
+        }
+    }
+
+    pub fn with_lights(mut self, lights: HittableList) -> Self {
+        self.lights = lights;
+        self
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use crate::model::vec3::Vec3;
+
+use super::{solid_color::SolidColor, texture::Texture};
+
+use Vec3 as Point3;
+
+// Solid 3D checker: alternates between two textures in cubes of side `scale`
+// filling space, so any surface cutting through it is checkered.
+pub struct CheckerTexture {
+    inv_scale: f64,
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+}
+
+impl CheckerTexture {
+    pub fn new(scale: f64, even: Arc<dyn Texture>, odd: Arc<dyn Texture>) -> Self {
+        Self {
+            inv_scale: 1.0 / scale,
+            even,
+            odd,
+        }
+    }
+
+    pub fn from_colors(scale: f64, c1: &Vec3, c2: &Vec3) -> Self {
+        CheckerTexture::new(
+            scale,
+            Arc::new(SolidColor::new(c1)),
+            Arc::new(SolidColor::new(c2)),
+        )
+    }
+}
+
+impl Texture for CheckerTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Vec3 {
+        let x = (self.inv_scale * p.x()).floor() as i64;
+        let y = (self.inv_scale * p.y()).floor() as i64;
+        let z = (self.inv_scale * p.z()).floor() as i64;
+
+        if (x + y + z).rem_euclid(2) == 0 {
+            self.even.value(u, v, p)
+        } else {
+            self.odd.value(u, v, p)
+        }
+    }
+}
+
+// Surface checker: alternates in a grid of `width` x `height` cells over the
+// (u, v) square, so the pattern follows the surface parameterization.
+pub struct UvCheckerTexture {
+    width: f64,
+    height: f64,
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+}
+
+impl UvCheckerTexture {
+    pub fn new(width: f64, height: f64, even: Arc<dyn Texture>, odd: Arc<dyn Texture>) -> Self {
+        Self {
+            width,
+            height,
+            even,
+            odd,
+        }
+    }
+
+    pub fn from_colors(width: f64, height: f64, c1: &Vec3, c2: &Vec3) -> Self {
+        UvCheckerTexture::new(
+            width,
+            height,
+            Arc::new(SolidColor::new(c1)),
+            Arc::new(SolidColor::new(c2)),
+        )
+    }
+}
+
+impl Texture for UvCheckerTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Vec3 {
+        let i = (u * self.width).floor() as i64;
+        let j = (v * self.height).floor() as i64;
+
+        if (i + j).rem_euclid(2) == 0 {
+            self.even.value(u, v, p)
+        } else {
+            self.odd.value(u, v, p)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checker_texture() {
+        let white = Vec3::new(1.0, 1.0, 1.0);
+        let black = Vec3::new(0.0, 0.0, 0.0);
+        let checker = CheckerTexture::from_colors(0.5, &white, &black);
+        assert_eq!(white, checker.value(0.0, 0.0, &Point3::new(0.1, 0.1, 0.1)));
+        assert_eq!(black, checker.value(0.0, 0.0, &Point3::new(0.6, 0.1, 0.1)));
+        assert_eq!(black, checker.value(0.0, 0.0, &Point3::new(-0.1, 0.1, 0.1)));
+        assert_eq!(
+            white,
+            checker.value(0.0, 0.0, &Point3::new(-0.1, -0.1, 0.1))
+        );
+    }
+
+    #[test]
+    fn test_uv_checker_texture() {
+        let white = Vec3::new(1.0, 1.0, 1.0);
+        let black = Vec3::new(0.0, 0.0, 0.0);
+        let checker = UvCheckerTexture::from_colors(4.0, 2.0, &white, &black);
+        let p = Point3::default();
+        assert_eq!(white, checker.value(0.1, 0.1, &p));
+        assert_eq!(black, checker.value(0.3, 0.1, &p));
+        assert_eq!(black, checker.value(0.1, 0.6, &p));
+        assert_eq!(white, checker.value(0.3, 0.6, &p));
+    }
+}
@@ -0,0 +1,148 @@
+use std::{path::Path, sync::Arc};
+
+use crate::{
+    loader::{
+        image::{load_image, Image},
+        LoadError,
+    },
+    model::vec3::Vec3,
+};
+
+use super::texture::Texture;
+
+use Vec3 as Point3;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Filter {
+    Nearest,
+    Bilinear,
+}
+
+// How texel coordinates outside the image are brought back inside it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum WrapMode {
+    Repeat,
+    Clamp,
+    Mirror,
+}
+
+impl WrapMode {
+    fn apply(&self, i: i64, n: usize) -> usize {
+        let n = n as i64;
+        let wrapped = match self {
+            WrapMode::Repeat => i.rem_euclid(n),
+            WrapMode::Clamp => i.clamp(0, n - 1),
+            WrapMode::Mirror => {
+                let m = i.rem_euclid(2 * n);
+                if m < n {
+                    m
+                } else {
+                    2 * n - 1 - m
+                }
+            }
+        };
+        wrapped as usize
+    }
+}
+
+// Maps an image over the (u, v) square, with v = 0 at the bottom row.
+pub struct ImageTexture {
+    image: Arc<Image>,
+    filter: Filter,
+    wrap: WrapMode,
+}
+
+impl ImageTexture {
+    pub fn new(image: Arc<Image>, filter: Filter, wrap: WrapMode) -> Self {
+        Self {
+            image,
+            filter,
+            wrap,
+        }
+    }
+
+    pub fn load(path: &Path, filter: Filter, wrap: WrapMode) -> Result<Self, LoadError> {
+        Ok(ImageTexture::new(Arc::new(load_image(path)?), filter, wrap))
+    }
+
+    fn texel(&self, x: i64, y: i64) -> Vec3 {
+        let i = self.wrap.apply(x, self.image.width());
+        let j = self.wrap.apply(y, self.image.height());
+        *self.image.pixel(i, j)
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f64, v: f64, _p: &Point3) -> Vec3 {
+        // Continuous texel coordinates, with y flipped so v runs upwards.
+        let x = u * self.image.width() as f64;
+        let y = (1.0 - v) * self.image.height() as f64;
+
+        match self.filter {
+            Filter::Nearest => self.texel(x.floor() as i64, y.floor() as i64),
+            Filter::Bilinear => {
+                // Texel centers sit at half-integer coordinates.
+                let x = x - 0.5;
+                let y = y - 0.5;
+                let x0 = x.floor();
+                let y0 = y.floor();
+                let tx = x - x0;
+                let ty = y - y0;
+                let (x0, y0) = (x0 as i64, y0 as i64);
+
+                (1.0 - tx) * (1.0 - ty) * self.texel(x0, y0)
+                    + tx * (1.0 - ty) * self.texel(x0 + 1, y0)
+                    + (1.0 - tx) * ty * self.texel(x0, y0 + 1)
+                    + tx * ty * self.texel(x0 + 1, y0 + 1)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2x1 image: black on the left, white on the right.
+    fn two_texels() -> Arc<Image> {
+        Arc::new(Image::new(
+            2,
+            1,
+            vec![Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 1.0, 1.0)],
+        ))
+    }
+
+    #[test]
+    fn test_wrap_modes() {
+        assert_eq!(1, WrapMode::Repeat.apply(-1, 2));
+        assert_eq!(0, WrapMode::Repeat.apply(2, 2));
+        assert_eq!(0, WrapMode::Clamp.apply(-5, 2));
+        assert_eq!(1, WrapMode::Clamp.apply(5, 2));
+        assert_eq!(0, WrapMode::Mirror.apply(-1, 2));
+        assert_eq!(1, WrapMode::Mirror.apply(2, 2));
+        assert_eq!(0, WrapMode::Mirror.apply(3, 2));
+        assert_eq!(0, WrapMode::Mirror.apply(4, 2));
+    }
+
+    #[test]
+    fn test_nearest_filter() {
+        let texture = ImageTexture::new(two_texels(), Filter::Nearest, WrapMode::Repeat);
+        let p = Point3::default();
+        assert_eq!(0.0, texture.value(0.25, 0.5, &p).x());
+        assert_eq!(1.0, texture.value(0.75, 0.5, &p).x());
+        assert_eq!(0.0, texture.value(1.25, 0.5, &p).x());
+    }
+
+    #[test]
+    fn test_bilinear_filter() {
+        let p = Point3::default();
+        let clamped = ImageTexture::new(two_texels(), Filter::Bilinear, WrapMode::Clamp);
+        assert_eq!(0.0, clamped.value(0.25, 0.5, &p).x());
+        assert_eq!(0.5, clamped.value(0.5, 0.5, &p).x());
+        assert_eq!(1.0, clamped.value(1.0, 0.5, &p).x());
+
+        // With repeat, the right edge blends back towards the left texel.
+        let repeated = ImageTexture::new(two_texels(), Filter::Bilinear, WrapMode::Repeat);
+        assert_eq!(0.5, repeated.value(1.0, 0.5, &p).x());
+    }
+}
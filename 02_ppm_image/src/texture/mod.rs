@@ -0,0 +1,8 @@
+pub mod checker;
+pub mod image;
+pub mod noise;
+pub mod perlin;
+pub mod solid_color;
+#[allow(clippy::module_inception)]
+pub mod texture;
+pub mod vertex_color;
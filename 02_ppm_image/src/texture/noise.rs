@@ -0,0 +1,81 @@
+use crate::model::vec3::Vec3;
+
+use super::{perlin::Perlin, texture::Texture};
+
+use Vec3 as Point3;
+
+// Plain Perlin noise, remapped from [-1, 1] to a gray level in [0, 1].
+pub struct NoiseTexture {
+    noise: Perlin,
+    scale: f64,
+}
+
+impl NoiseTexture {
+    pub fn new(seed: u64, scale: f64) -> Self {
+        Self {
+            noise: Perlin::new(seed),
+            scale,
+        }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f64, _v: f64, p: &Point3) -> Vec3 {
+        Vec3::new(1.0, 1.0, 1.0) * 0.5 * (1.0 + self.noise.noise(&(self.scale * p)))
+    }
+}
+
+// Marble veins: a sine wave along z whose phase is perturbed by turbulence.
+pub struct MarbleTexture {
+    noise: Perlin,
+    scale: f64,
+    color: Vec3,
+}
+
+impl MarbleTexture {
+    pub fn new(seed: u64, scale: f64, color: &Vec3) -> Self {
+        Self {
+            noise: Perlin::new(seed),
+            scale,
+            color: *color,
+        }
+    }
+}
+
+impl Texture for MarbleTexture {
+    fn value(&self, _u: f64, _v: f64, p: &Point3) -> Vec3 {
+        let phase = self.scale * p.z() + 10.0 * self.noise.turb(p, 7);
+        self.color * 0.5 * (1.0 + phase.sin())
+    }
+}
+
+// Wood grain: concentric rings around the y axis, wobbled by turbulence and
+// shaded between a light and a dark color.
+pub struct WoodTexture {
+    noise: Perlin,
+    scale: f64,
+    light: Vec3,
+    dark: Vec3,
+}
+
+impl WoodTexture {
+    pub fn new(seed: u64, scale: f64, light: &Vec3, dark: &Vec3) -> Self {
+        Self {
+            noise: Perlin::new(seed),
+            scale,
+            light: *light,
+            dark: *dark,
+        }
+    }
+}
+
+impl Texture for WoodTexture {
+    fn value(&self, _u: f64, _v: f64, p: &Point3) -> Vec3 {
+        let q = self.scale * p;
+        let radius = (q.x() * q.x() + q.z() * q.z()).sqrt() + 2.0 * self.noise.turb(&q, 4);
+        let ring = radius - radius.floor();
+        // Sharpen the rings so late wood stays thin.
+        let t = ring.powf(3.0);
+        (1.0 - t) * self.light + t * self.dark
+    }
+}
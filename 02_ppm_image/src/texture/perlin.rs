@@ -0,0 +1,149 @@
+use crate::{model::vec3::Vec3, util::rng::Pcg32};
+
+use Vec3 as Point3;
+
+const POINT_COUNT: usize = 256;
+
+// Gradient (Perlin) noise over a lattice of random unit vectors. Everything
+// random is drawn from a generator seeded by the caller, so the same seed
+// always produces the same pattern.
+pub struct Perlin {
+    ranvec: Vec<Vec3>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>,
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Pcg32::new(seed, 0);
+
+        let ranvec = (0..POINT_COUNT)
+            .map(|_| {
+                Vec3::new(
+                    2.0 * rng.next_f64() - 1.0,
+                    2.0 * rng.next_f64() - 1.0,
+                    2.0 * rng.next_f64() - 1.0,
+                )
+                .unit_vector()
+            })
+            .collect();
+
+        Self {
+            ranvec,
+            perm_x: Perlin::generate_perm(&mut rng),
+            perm_y: Perlin::generate_perm(&mut rng),
+            perm_z: Perlin::generate_perm(&mut rng),
+        }
+    }
+
+    // Smooth noise in roughly [-1, 1].
+    pub fn noise(&self, p: &Point3) -> f64 {
+        let u = p.x() - p.x().floor();
+        let v = p.y() - p.y().floor();
+        let w = p.z() - p.z().floor();
+
+        let i = p.x().floor() as i64;
+        let j = p.y().floor() as i64;
+        let k = p.z().floor() as i64;
+
+        let mut c = [[[Vec3::default(); 2]; 2]; 2];
+        for (di, plane) in c.iter_mut().enumerate() {
+            for (dj, row) in plane.iter_mut().enumerate() {
+                for (dk, corner) in row.iter_mut().enumerate() {
+                    *corner = self.ranvec[self.perm_x[Perlin::wrap(i + di as i64)]
+                        ^ self.perm_y[Perlin::wrap(j + dj as i64)]
+                        ^ self.perm_z[Perlin::wrap(k + dk as i64)]];
+                }
+            }
+        }
+
+        Perlin::perlin_interp(&c, u, v, w)
+    }
+
+    // Sum of `depth` octaves of noise, each at double the frequency and half
+    // the weight of the previous one.
+    pub fn turb(&self, p: &Point3, depth: usize) -> f64 {
+        let mut accum = 0.0;
+        let mut temp_p = *p;
+        let mut weight = 1.0;
+
+        for _ in 0..depth {
+            accum += weight * self.noise(&temp_p);
+            weight *= 0.5;
+            temp_p *= 2.0;
+        }
+
+        accum.abs()
+    }
+
+    fn wrap(i: i64) -> usize {
+        (i & (POINT_COUNT as i64 - 1)) as usize
+    }
+
+    fn generate_perm(rng: &mut Pcg32) -> Vec<usize> {
+        let mut p: Vec<usize> = (0..POINT_COUNT).collect();
+
+        // Fisher-Yates shuffle.
+        for i in (1..POINT_COUNT).rev() {
+            let target = (rng.next_f64() * (i + 1) as f64) as usize;
+            p.swap(i, target);
+        }
+
+        p
+    }
+
+    // Trilinear interpolation of the corner gradients' contributions, with
+    // Hermite smoothing of the weights to hide the lattice.
+    fn perlin_interp(c: &[[[Vec3; 2]; 2]; 2], u: f64, v: f64, w: f64) -> f64 {
+        let uu = u * u * (3.0 - 2.0 * u);
+        let vv = v * v * (3.0 - 2.0 * v);
+        let ww = w * w * (3.0 - 2.0 * w);
+        let mut accum = 0.0;
+
+        for (i, plane) in c.iter().enumerate() {
+            for (j, row) in plane.iter().enumerate() {
+                for (k, corner) in row.iter().enumerate() {
+                    let (fi, fj, fk) = (i as f64, j as f64, k as f64);
+                    let weight_v = Vec3::new(u - fi, v - fj, w - fk);
+                    accum += (fi * uu + (1.0 - fi) * (1.0 - uu))
+                        * (fj * vv + (1.0 - fj) * (1.0 - vv))
+                        * (fk * ww + (1.0 - fk) * (1.0 - ww))
+                        * corner.dot(&weight_v);
+                }
+            }
+        }
+
+        accum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perlin_is_reproducible() {
+        let a = Perlin::new(42);
+        let b = Perlin::new(42);
+        let c = Perlin::new(7);
+        let p = Point3::new(1.3, -2.7, 0.45);
+        assert_eq!(a.noise(&p), b.noise(&p));
+        assert_eq!(a.turb(&p, 7), b.turb(&p, 7));
+        assert_ne!(a.noise(&p), c.noise(&p));
+    }
+
+    #[test]
+    fn test_perlin_range() {
+        let perlin = Perlin::new(1);
+        for i in 0..1000 {
+            let t = i as f64 * 0.137;
+            let p = Point3::new(t, 1.7 * t, -0.3 * t);
+            let n = perlin.noise(&p);
+            assert!((-1.0..=1.0).contains(&n));
+            assert!(perlin.turb(&p, 7) >= 0.0);
+        }
+        // Gradient noise vanishes on the lattice points.
+        assert_eq!(0.0, perlin.noise(&Point3::new(3.0, -2.0, 5.0)));
+    }
+}
@@ -0,0 +1,25 @@
+use crate::model::vec3::Vec3;
+
+use super::texture::Texture;
+
+use Vec3 as Point3;
+
+pub struct SolidColor {
+    color_value: Vec3,
+}
+
+impl SolidColor {
+    pub fn new(c: &Vec3) -> Self {
+        Self { color_value: *c }
+    }
+
+    pub fn from_rgb(red: f64, green: f64, blue: f64) -> Self {
+        SolidColor::new(&Vec3::new(red, green, blue))
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: f64, _v: f64, _p: &Point3) -> Vec3 {
+        self.color_value
+    }
+}
@@ -0,0 +1,9 @@
+use crate::model::vec3::Vec3;
+
+use Vec3 as Point3;
+
+// A color that varies over a surface, looked up by the hit's surface
+// coordinates (u, v) and/or its position p.
+pub trait Texture: Send + Sync {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Vec3;
+}
@@ -0,0 +1,64 @@
+use crate::model::vec3::Vec3;
+
+use super::texture::Texture;
+
+use Vec3 as Point3;
+
+// Per-vertex colors of a triangle mesh, blended across each face by the hit's
+// barycentric coordinates. A single texture (and material) serves the whole
+// mesh: face i must carry the texture coordinates from `face_uvs(i)`, which
+// encode both the face and the barycentric weights in (u, v).
+pub struct VertexColorTexture {
+    colors: Vec<[Vec3; 3]>, // per face, in corner order
+}
+
+impl VertexColorTexture {
+    pub fn new(colors: Vec<[Vec3; 3]>) -> Self {
+        Self { colors }
+    }
+
+    // Corner texture coordinates for face i. Interpolated they give
+    // u = 2i + b1 and v = b2, so u stays within [2i, 2i + 1] and the face
+    // index survives the round trip even on the face's edges.
+    pub fn face_uvs(i: usize) -> [(f64, f64); 3] {
+        let u = 2.0 * i as f64;
+        [(u, 0.0), (u + 1.0, 0.0), (u, 1.0)]
+    }
+}
+
+impl Texture for VertexColorTexture {
+    fn value(&self, u: f64, v: f64, _p: &Point3) -> Vec3 {
+        let i = (u / 2.0).floor().max(0.0) as usize;
+        let [c0, c1, c2] = match self.colors.get(i) {
+            Some(colors) => *colors,
+            None => return Vec3::new(0.0, 0.0, 0.0),
+        };
+        let b1 = (u - 2.0 * i as f64).clamp(0.0, 1.0);
+        let b2 = v.clamp(0.0, 1.0 - b1);
+        (1.0 - b1 - b2) * c0 + b1 * c1 + b2 * c2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vertex_color_texture() {
+        let red = Vec3::new(1.0, 0.0, 0.0);
+        let green = Vec3::new(0.0, 1.0, 0.0);
+        let blue = Vec3::new(0.0, 0.0, 1.0);
+        let texture = VertexColorTexture::new(vec![[red, green, blue], [blue, blue, green]]);
+        let p = Point3::default();
+
+        let [uv0, uv1, uv2] = VertexColorTexture::face_uvs(0);
+        assert_eq!(red, texture.value(uv0.0, uv0.1, &p));
+        assert_eq!(green, texture.value(uv1.0, uv1.1, &p));
+        assert_eq!(blue, texture.value(uv2.0, uv2.1, &p));
+        assert_eq!(Vec3::new(0.5, 0.25, 0.25), texture.value(0.25, 0.25, &p));
+
+        let [_, uv1, _] = VertexColorTexture::face_uvs(1);
+        assert_eq!(blue, texture.value(uv1.0, uv1.1, &p));
+        assert_eq!(green, texture.value(2.0, 1.0, &p));
+    }
+}
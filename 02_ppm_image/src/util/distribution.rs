@@ -0,0 +1,158 @@
+// Piecewise-constant probability distributions, sampled by inverting their
+// cumulative distribution functions.
+
+// A distribution over [0,1) split into equal-width buckets, each with a
+// probability proportional to its (non-negative) function value.
+pub struct Distribution1D {
+    func: Vec<f64>,
+    cdf: Vec<f64>,
+    func_int: f64,
+}
+
+impl Distribution1D {
+    pub fn new(func: &[f64]) -> Self {
+        assert!(!func.is_empty());
+        let n = func.len();
+        let mut cdf = Vec::with_capacity(n + 1);
+        cdf.push(0.0);
+        for (i, f) in func.iter().enumerate() {
+            cdf.push(cdf[i] + f / n as f64);
+        }
+
+        // An all-zero function falls back to a uniform distribution.
+        let func_int = cdf[n];
+        for (i, c) in cdf.iter_mut().enumerate().skip(1) {
+            *c = if func_int == 0.0 {
+                i as f64 / n as f64
+            } else {
+                *c / func_int
+            };
+        }
+
+        Self {
+            func: func.to_vec(),
+            cdf,
+            func_int,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.func.len()
+    }
+
+    // The integral of the function over [0,1).
+    pub fn integral(&self) -> f64 {
+        self.func_int
+    }
+
+    // Maps a uniform u in [0,1) to (x, pdf(x), bucket index).
+    pub fn sample_continuous(&self, u: f64) -> (f64, f64, usize) {
+        // The last bucket whose cdf start is <= u.
+        let offset = self
+            .cdf
+            .partition_point(|&c| c <= u)
+            .saturating_sub(1)
+            .min(self.count() - 1);
+
+        let mut du = u - self.cdf[offset];
+        let width = self.cdf[offset + 1] - self.cdf[offset];
+        if width > 0.0 {
+            du /= width;
+        }
+
+        let x = (offset as f64 + du) / self.count() as f64;
+        (x, self.pdf_at(offset), offset)
+    }
+
+    // Density at x in [0,1).
+    pub fn pdf(&self, x: f64) -> f64 {
+        let offset = ((x * self.count() as f64) as usize).min(self.count() - 1);
+        self.pdf_at(offset)
+    }
+
+    fn pdf_at(&self, offset: usize) -> f64 {
+        if self.func_int == 0.0 {
+            1.0
+        } else {
+            self.func[offset] / self.func_int
+        }
+    }
+}
+
+// A distribution over [0,1)² given as a grid of function values, row by row.
+// A row (the second coordinate) is picked from the marginal distribution,
+// then a column from that row's conditional distribution.
+pub struct Distribution2D {
+    conditional: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    pub fn new(func: &[f64], width: usize, height: usize) -> Self {
+        assert_eq!(width * height, func.len());
+        let conditional: Vec<Distribution1D> =
+            func.chunks_exact(width).map(Distribution1D::new).collect();
+        let marginal_func: Vec<f64> = conditional.iter().map(|c| c.integral()).collect();
+
+        Self {
+            conditional,
+            marginal: Distribution1D::new(&marginal_func),
+        }
+    }
+
+    // Maps uniform (u0, u1) to a point (x, y) and its density.
+    pub fn sample_continuous(&self, u0: f64, u1: f64) -> ((f64, f64), f64) {
+        let (y, pdf_y, row) = self.marginal.sample_continuous(u1);
+        let (x, pdf_x, _) = self.conditional[row].sample_continuous(u0);
+        ((x, y), pdf_x * pdf_y)
+    }
+
+    pub fn pdf(&self, x: f64, y: f64) -> f64 {
+        let rows = self.conditional.len();
+        let row = ((y * rows as f64) as usize).min(rows - 1);
+        self.conditional[row].pdf(x) * self.marginal.pdf(y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distribution_1d() {
+        let d = Distribution1D::new(&[1.0, 3.0]);
+        assert_eq!(2.0, d.integral());
+
+        // A quarter of the mass lies in the first half.
+        let (x, pdf, offset) = d.sample_continuous(0.125);
+        assert_eq!((0.25, 0.5, 0), (x, pdf, offset));
+        let (x, pdf, offset) = d.sample_continuous(0.625);
+        assert_eq!((0.75, 1.5, 1), (x, pdf, offset));
+        assert_eq!(1.5, d.pdf(0.9));
+
+        let uniform = Distribution1D::new(&[0.0, 0.0]);
+        assert_eq!((0.3, 1.0, 0), uniform.sample_continuous(0.3));
+    }
+
+    #[test]
+    fn test_distribution_1d_skips_empty_buckets() {
+        let d = Distribution1D::new(&[0.0, 1.0, 0.0]);
+        for u in [0.0, 0.5, 0.999] {
+            let (x, pdf, offset) = d.sample_continuous(u);
+            assert_eq!(1, offset);
+            assert!((1.0 / 3.0..2.0 / 3.0).contains(&x));
+            assert_eq!(3.0, pdf);
+        }
+    }
+
+    #[test]
+    fn test_distribution_2d() {
+        // All the mass is in the bottom-right cell of a 2x2 grid.
+        let d = Distribution2D::new(&[0.0, 0.0, 0.0, 1.0], 2, 2);
+        let ((x, y), pdf) = d.sample_continuous(0.5, 0.5);
+        assert_eq!((0.75, 0.75), (x, y));
+        assert_eq!(4.0, pdf);
+        assert_eq!(4.0, d.pdf(x, y));
+        assert_eq!(0.0, d.pdf(0.25, 0.75));
+    }
+}
@@ -1 +1,3 @@
+pub mod distribution;
+pub mod rng;
 pub mod rtweekend;
@@ -1,22 +1,10 @@
-use rand::Rng;
-
-pub const INFINITY: f64 = std::f64::INFINITY;
+pub const INFINITY: f64 = f64::INFINITY;
 pub const PI: f64 = std::f64::consts::PI;
 
 pub fn degrees_to_radians(degrees: f64) -> f64 {
     degrees * PI / 180.0
 }
 
-pub fn random_double() -> f64 {
-    let mut rng = rand::thread_rng();
-    rng.gen::<f64>()
-}
-
-pub fn random_double_by_range(min: f64, max: f64) -> f64 {
-    let mut rng = rand::thread_rng();
-    rng.gen_range(min..max)
-}
-
 pub fn clamp(x: f64, min: f64, max: f64) -> f64 {
     if x < min {
         return min;
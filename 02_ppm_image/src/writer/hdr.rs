@@ -0,0 +1,119 @@
+use std::io::{self, Write};
+
+use crate::{film::Film, model::vec3::Vec3};
+
+// Writes Radiance RGBE with flat (unencoded) scanlines, which every reader
+// accepts.
+pub(super) fn write_rgbe(film: &Film, out: &mut dyn Write) -> io::Result<()> {
+    write!(
+        out,
+        "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+        film.height(),
+        film.width()
+    )?;
+    let data: Vec<u8> = film.colors().flat_map(|c| vec3_to_rgbe(&c)).collect();
+    out.write_all(&data)
+}
+
+// Three 8-bit mantissas sharing the exponent of the largest channel.
+fn vec3_to_rgbe(c: &Vec3) -> [u8; 4] {
+    let (r, g, b) = (c.x().max(0.0), c.y().max(0.0), c.z().max(0.0));
+    let v = r.max(g).max(b);
+    if v.is_nan() || v < 1e-32 {
+        return [0, 0, 0, 0];
+    }
+    let e = frexp_exponent(v).clamp(-128, 127);
+    let scale = 256.0 / 2f64.powi(e);
+    let mantissa = |x: f64| (x * scale).min(255.0) as u8;
+    [mantissa(r), mantissa(g), mantissa(b), (e + 128) as u8]
+}
+
+// The exponent e with v = m · 2^e and m in [0.5, 1), as C's frexp gives it,
+// read straight from the bits of a normal (or infinite) v.
+fn frexp_exponent(v: f64) -> i32 {
+    ((v.to_bits() >> 52) & 0x7ff) as i32 - 1022
+}
+
+// Writes a little-endian RGB Portable Float Map, rows bottom-up as the format
+// requires.
+pub(super) fn write_pfm(film: &Film, out: &mut dyn Write) -> io::Result<()> {
+    write!(out, "PF\n{} {}\n-1.0\n", film.width(), film.height())?;
+    let colors: Vec<Vec3> = film.colors().collect();
+    let mut data = Vec::with_capacity(colors.len() * 12);
+    for row in colors.chunks_exact(film.width()).rev() {
+        for c in row {
+            for value in [c.x(), c.y(), c.z()] {
+                data.extend_from_slice(&(value as f32).to_le_bytes());
+            }
+        }
+    }
+    out.write_all(&data)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{
+        filter::box_filter::BoxFilter,
+        loader::image::parse_image,
+        writer::image::{write_image, ImageFormat},
+    };
+
+    #[test]
+    fn test_hdr_formats_round_trip() {
+        // Values beyond 1 must survive.
+        let mut film = Film::new(2, 2);
+        let colors = [
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(12.5, 0.25, 3.0),
+            Vec3::new(0.001, 0.002, 0.004),
+            Vec3::new(1.0, 1.0, 1.0),
+        ];
+        for (i, color) in colors.iter().enumerate() {
+            let (x, y) = ((i % 2) as f64 + 0.5, (i / 2) as f64 + 0.5);
+            film.add_sample(x, y, color, &BoxFilter::new(0.5));
+        }
+
+        for (format, tolerance) in [(ImageFormat::Hdr, 1.0 / 128.0), (ImageFormat::Pfm, 1e-6)] {
+            let mut bytes = Vec::new();
+            write_image(&film, format, &mut bytes).unwrap();
+            let image = parse_image(&bytes, Path::new("test")).unwrap();
+            for (i, color) in colors.iter().enumerate() {
+                let decoded = image.pixel(i % 2, i / 2);
+                for axis in 0..3 {
+                    let max = color.x().max(color.y()).max(color.z());
+                    assert!(
+                        (decoded[axis] - color[axis]).abs() <= tolerance * max,
+                        "{:?}: {} vs {}",
+                        format,
+                        decoded,
+                        color
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rgbe_mantissa_is_normalized() {
+        // Just below a power of two, a log2-based exponent can come out one
+        // too high and leave the mantissa below 128.
+        for v in [
+            1.0,
+            0.5,
+            3.0,
+            1e10,
+            f64::from_bits(1.0f64.to_bits() - 1),
+            f64::from_bits(2f64.powi(-20).to_bits() - 1),
+            f64::from_bits(2f64.powi(40).to_bits() - 1),
+        ] {
+            let [r, g, _, e] = vec3_to_rgbe(&Vec3::new(v, v / 2.0, 0.0));
+            assert!(r >= 128, "{}: mantissa {}", v, r);
+            let decoded = r as f64 * 2f64.powi(e as i32 - (128 + 8));
+            assert!((decoded - v).abs() <= v / 128.0);
+            assert_eq!(r / 2, g);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{film::Film, model::color::Color};
+
+use super::{
+    hdr::{write_pfm, write_rgbe},
+    WriteError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    // Plain-text P3, 8 bits per channel.
+    Ppm,
+    Png,
+    // Radiance RGBE.
+    Hdr,
+    Pfm,
+}
+
+impl ImageFormat {
+    // The format named by a path's extension, ignoring case.
+    pub fn from_path(path: &Path) -> Option<ImageFormat> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "ppm" => Some(ImageFormat::Ppm),
+            "png" => Some(ImageFormat::Png),
+            "hdr" => Some(ImageFormat::Hdr),
+            "pfm" => Some(ImageFormat::Pfm),
+            _ => None,
+        }
+    }
+}
+
+// Saves a film in the format its extension names. PPM and PNG are gamma 2.0
+// encoded and clamped to [0,1], exactly like the P3 the renderer has always
+// printed; HDR and PFM keep the linear radiance.
+pub fn save_image(film: &Film, path: &Path) -> Result<(), WriteError> {
+    let format = ImageFormat::from_path(path).ok_or_else(|| WriteError::UnknownFormat {
+        path: path.to_path_buf(),
+    })?;
+    let file = File::create(path).map_err(|e| WriteError::io(path, e))?;
+    let mut out = BufWriter::new(file);
+    write_image(film, format, &mut out)
+        .and_then(|_| out.flush())
+        .map_err(|e| WriteError::io(path, e))
+}
+
+pub fn write_image(film: &Film, format: ImageFormat, out: &mut dyn Write) -> io::Result<()> {
+    match format {
+        ImageFormat::Ppm => write_ppm(film, out),
+        ImageFormat::Png => write_png(film, out),
+        ImageFormat::Hdr => write_rgbe(film, out),
+        ImageFormat::Pfm => write_pfm(film, out),
+    }
+}
+
+fn write_ppm(film: &Film, out: &mut dyn Write) -> io::Result<()> {
+    write!(out, "P3\n{} {}\n255\n", film.width(), film.height())?;
+    for color in film.colors() {
+        write!(out, "{}", color.as_color_repr())?;
+    }
+    Ok(())
+}
+
+fn write_png(film: &Film, out: &mut dyn Write) -> io::Result<()> {
+    let mut encoder = png::Encoder::new(out, film.width() as u32, film.height() as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let data: Vec<u8> = film.colors().flat_map(|c| c.as_rgb8()).collect();
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&data))
+        .map_err(io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{filter::box_filter::BoxFilter, loader::image::parse_image, model::vec3::Vec3};
+
+    // A 3×2 film with a distinct color per pixel.
+    fn test_film() -> Film {
+        let mut film = Film::new(3, 2);
+        for y in 0..2 {
+            for x in 0..3 {
+                let color = Vec3::new(0.1 * x as f64, 0.2 * y as f64, 0.5 + 0.2 * x as f64);
+                film.add_sample(x as f64 + 0.5, y as f64 + 0.5, &color, &BoxFilter::new(0.5));
+            }
+        }
+        film
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(
+            Some(ImageFormat::Png),
+            ImageFormat::from_path(Path::new("out/render.PNG"))
+        );
+        assert_eq!(None, ImageFormat::from_path(Path::new("render.jpg")));
+        assert_eq!(None, ImageFormat::from_path(Path::new("render")));
+    }
+
+    #[test]
+    fn test_ldr_formats_round_trip() {
+        let film = test_film();
+        for format in [ImageFormat::Ppm, ImageFormat::Png] {
+            let mut bytes = Vec::new();
+            write_image(&film, format, &mut bytes).unwrap();
+            let image = parse_image(&bytes, Path::new("test")).unwrap();
+            assert_eq!((3, 2), (image.width(), image.height()));
+            for y in 0..2 {
+                for x in 0..3 {
+                    let error = *image.pixel(x, y) - film.pixel(x, y);
+                    assert!(error.length() < 0.01, "{:?} at ({}, {})", format, x, y);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ppm_matches_color_repr() {
+        let mut bytes = Vec::new();
+        write_image(&test_film(), ImageFormat::Ppm, &mut bytes).unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+        assert!(text.starts_with("P3\n3 2\n255\n0 0 181\n80 0 214\n"));
+    }
+}
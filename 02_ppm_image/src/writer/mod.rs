@@ -0,0 +1,47 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    io,
+    path::{Path, PathBuf},
+};
+
+pub mod hdr;
+pub mod image;
+
+#[derive(Debug)]
+pub enum WriteError {
+    Io { path: PathBuf, source: io::Error },
+    // The file extension names no format we can write.
+    UnknownFormat { path: PathBuf },
+}
+
+impl WriteError {
+    pub fn io(path: &Path, source: io::Error) -> Self {
+        WriteError::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+}
+
+impl Display for WriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WriteError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            WriteError::UnknownFormat { path } => write!(
+                f,
+                "{}: unrecognized image extension (expected .ppm, .png, .hdr or .pfm)",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl Error for WriteError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WriteError::Io { source, .. } => Some(source),
+            WriteError::UnknownFormat { .. } => None,
+        }
+    }
+}